@@ -0,0 +1,29 @@
+// Grille spatiale — passe 1 : remise à zéro des compteurs de cellules
+
+// Paramètres de la grille (voir GridParams côté Rust)
+struct GridParams {
+    grid_dim: u32,
+    num_cells: u32,
+    cell_size: f32,
+    half_world: f32,
+    enabled: u32,
+}
+@group(0) @binding(0) var<uniform> grid: GridParams;
+
+// Nombre de particules par cellule
+@group(0) @binding(1) var<storage, read_write> cell_counts: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    // Dispatch en 2D au-delà de la limite de groupes par dimension (voir `dispatch_size`)
+    let cell = global_id.y * num_workgroups.x * 64u + global_id.x;
+
+    if (grid.enabled == 0u || cell >= grid.num_cells) {
+        return;
+    }
+
+    cell_counts[cell] = 0u;
+}
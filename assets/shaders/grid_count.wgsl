@@ -0,0 +1,56 @@
+// Grille spatiale — passe 2 : attribution d'une cellule à chaque particule
+
+@group(0) @binding(0) var<uniform> num_particles: u32;
+
+// Positions des particules (x, y, z, type)
+@group(0) @binding(1) var<storage, read> positions: array<vec4<f32>>;
+
+// Vélocités des particules (x, y, z, index de simulation)
+@group(0) @binding(2) var<storage, read> velocities: array<vec4<f32>>;
+
+// Paramètres de la grille (voir GridParams côté Rust)
+struct GridParams {
+    grid_dim: u32,
+    num_cells: u32,
+    cell_size: f32,
+    half_world: f32,
+    enabled: u32,
+}
+@group(0) @binding(3) var<uniform> grid: GridParams;
+
+// Nombre de particules par cellule
+@group(0) @binding(4) var<storage, read_write> cell_counts: array<atomic<u32>>;
+
+// Cellule de chaque particule et rang de la particule dans cette cellule
+@group(0) @binding(5) var<storage, read_write> particle_cells: array<vec2<u32>>;
+
+// Doit rester identique à `cell_coords` dans particle_compute.wgsl
+fn cell_coords(pos: vec3<f32>) -> vec3<i32> {
+    let coords = vec3<i32>(floor((pos + vec3<f32>(grid.half_world)) / grid.cell_size));
+    return clamp(coords, vec3<i32>(0), vec3<i32>(i32(grid.grid_dim) - 1));
+}
+
+// Doit rester identique à `cell_index` dans particle_compute.wgsl
+fn cell_index(sim_index: u32, coords: vec3<i32>) -> u32 {
+    let dim = grid.grid_dim;
+    return sim_index * dim * dim * dim + (u32(coords.z) * dim + u32(coords.y)) * dim + u32(coords.x);
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    // Dispatch en 2D au-delà de la limite de groupes par dimension (voir `dispatch_size`)
+    let particle_index = global_id.y * num_workgroups.x * 64u + global_id.x;
+
+    if (grid.enabled == 0u || particle_index >= num_particles) {
+        return;
+    }
+
+    let sim_index = u32(velocities[particle_index].w);
+    let cell = cell_index(sim_index, cell_coords(positions[particle_index].xyz));
+    let slot = atomicAdd(&cell_counts[cell], 1u);
+
+    particle_cells[particle_index] = vec2<u32>(cell, slot);
+}
@@ -0,0 +1,61 @@
+// Grille spatiale — passe 3 : somme préfixe exclusive des compteurs
+// Un seul groupe de travail : chaque thread traite un bloc contigu de cellules.
+
+// Paramètres de la grille (voir GridParams côté Rust)
+struct GridParams {
+    grid_dim: u32,
+    num_cells: u32,
+    cell_size: f32,
+    half_world: f32,
+    enabled: u32,
+}
+@group(0) @binding(0) var<uniform> grid: GridParams;
+
+// Nombre de particules par cellule
+@group(0) @binding(1) var<storage, read> cell_counts: array<u32>;
+
+// Début de chaque cellule dans `sorted_indices` (num_cells + 1 entrées)
+@group(0) @binding(2) var<storage, read_write> cell_starts: array<u32>;
+
+const THREADS: u32 = 256u;
+
+var<workgroup> block_sums: array<u32, 256>;
+
+@compute @workgroup_size(256, 1, 1)
+fn main(@builtin(local_invocation_id) local_id: vec3<u32>) {
+    if (grid.enabled == 0u) {
+        return;
+    }
+
+    let thread = local_id.x;
+    let block_size = (grid.num_cells + THREADS - 1u) / THREADS;
+    let block_start = min(thread * block_size, grid.num_cells);
+    let block_end = min(block_start + block_size, grid.num_cells);
+
+    // Somme locale de chaque bloc
+    var sum = 0u;
+    for (var cell = block_start; cell < block_end; cell++) {
+        sum += cell_counts[cell];
+    }
+    block_sums[thread] = sum;
+    workgroupBarrier();
+
+    // Préfixe exclusif des blocs (256 valeurs, séquentiel)
+    if (thread == 0u) {
+        var running = 0u;
+        for (var i = 0u; i < THREADS; i++) {
+            let value = block_sums[i];
+            block_sums[i] = running;
+            running += value;
+        }
+        cell_starts[grid.num_cells] = running;
+    }
+    workgroupBarrier();
+
+    // Préfixe exclusif à l'intérieur de chaque bloc
+    var running = block_sums[thread];
+    for (var cell = block_start; cell < block_end; cell++) {
+        cell_starts[cell] = running;
+        running += cell_counts[cell];
+    }
+}
@@ -0,0 +1,38 @@
+// Grille spatiale — passe 4 : tri des indices de particules par cellule
+
+@group(0) @binding(0) var<uniform> num_particles: u32;
+
+// Paramètres de la grille (voir GridParams côté Rust)
+struct GridParams {
+    grid_dim: u32,
+    num_cells: u32,
+    cell_size: f32,
+    half_world: f32,
+    enabled: u32,
+}
+@group(0) @binding(1) var<uniform> grid: GridParams;
+
+// Cellule de chaque particule et rang de la particule dans cette cellule
+@group(0) @binding(2) var<storage, read> particle_cells: array<vec2<u32>>;
+
+// Début de chaque cellule dans `sorted_indices`
+@group(0) @binding(3) var<storage, read> cell_starts: array<u32>;
+
+// Indices de particules regroupés par cellule
+@group(0) @binding(4) var<storage, read_write> sorted_indices: array<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    // Dispatch en 2D au-delà de la limite de groupes par dimension (voir `dispatch_size`)
+    let particle_index = global_id.y * num_workgroups.x * 64u + global_id.x;
+
+    if (grid.enabled == 0u || particle_index >= num_particles) {
+        return;
+    }
+
+    let cell_and_slot = particle_cells[particle_index];
+    sorted_indices[cell_starts[cell_and_slot.x] + cell_and_slot.y] = particle_index;
+}
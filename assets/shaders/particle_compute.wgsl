@@ -9,7 +9,7 @@
 // Positions des particules (x, y, z, type)
 @group(0) @binding(4) var<storage, read> positions: array<vec4<f32>>;
 
-// Vélocités des particules (x, y, z, unused)
+// Vélocités des particules (x, y, z, index de simulation)
 @group(0) @binding(5) var<storage, read> velocities: array<vec4<f32>>;
 
 // Nouvelles positions (output)
@@ -18,64 +18,193 @@
 // Nouvelles vélocités (output)
 @group(0) @binding(7) var<storage, read_write> new_velocities: array<vec4<f32>>;
 
-// Matrice des forces d'interaction entre types (format linéaire)
+// Matrices des forces d'interaction entre types, une par simulation (format linéaire)
 @group(0) @binding(8) var<storage, read> force_matrix: array<f32>;
 
-// Constantes physiques
+// Plage de particules de chaque simulation (offset, nombre)
+@group(0) @binding(9) var<storage, read> sim_ranges: array<vec2<u32>>;
+
+// Paramètres physiques (voir PhysicsParams côté Rust)
+struct PhysicsParams {
+    force_model: u32,
+    beta: f32,
+    max_force_range: f32,
+    force_scale: f32,
+    // Facteur d'amortissement par pas, dérivé de la demi-vie et de dt côté CPU
+    friction: f32,
+    max_velocity: f32,
+    min_distance: f32,
+    boundary_mode: u32,
+    restitution: f32,
+    wall_stiffness: f32,
+    centering_strength: f32,
+    // 2 : mouvement limité au plan z = 0
+    dimensions: u32,
+}
+@group(0) @binding(10) var<uniform> params: PhysicsParams;
+
+// Paramètres de la grille spatiale (voir GridParams côté Rust)
+struct GridParams {
+    grid_dim: u32,
+    num_cells: u32,
+    cell_size: f32,
+    half_world: f32,
+    enabled: u32,
+}
+@group(0) @binding(11) var<uniform> grid: GridParams;
+
+// Début de chaque cellule dans `sorted_indices` (num_cells + 1 entrées)
+@group(0) @binding(12) var<storage, read> cell_starts: array<u32>;
+
+// Indices de particules regroupés par cellule (voir grid_scatter.wgsl)
+@group(0) @binding(13) var<storage, read> sorted_indices: array<u32>;
+
+// Propriétés physiques de chaque type, par simulation (voir TypeProperties côté Rust)
+struct TypeProperties {
+    // Divise l'accélération
+    mass: f32,
+    // Fraction de la portée des forces subies
+    radius: f32,
+    // Multiplie la vitesse max
+    max_speed: f32,
+    // Exposant de la friction par pas
+    damping: f32,
+}
+@group(0) @binding(14) var<storage, read> type_properties: array<TypeProperties>;
+
+// Modèles de force (voir ForceModel::shader_id)
+const FORCE_MODEL_PARTICLE_LIFE: u32 = 0u;
+const FORCE_MODEL_INVERSE_SQUARE: u32 = 1u;
+
+// Modes de bord (voir BoundaryMode::shader_id)
+const BOUNDARY_WRAP: u32 = 0u;
+const BOUNDARY_REFLECT: u32 = 1u;
+const BOUNDARY_SOFT_WALL: u32 = 2u;
+const BOUNDARY_UNBOUNDED: u32 = 3u;
+
+// Épaisseur du mur mou, en fraction de la taille du monde
+const WALL_THICKNESS_RATIO: f32 = 0.1;
+
+// Garde-fou numérique du modèle en 1/r²
 const MAX_FORCE: f32 = 1000000.0;
-const MIN_DISTANCE: f32 = 0.5;
-const MAX_DISTANCE: f32 = 50.0;
-const FRICTION: f32 = 0.98;
-const MAX_VELOCITY: f32 = 10000.0;
-
-// Fonction pour obtenir la force entre deux types de particules
-fn get_force_between_types(type_a: u32, type_b: u32) -> f32 {
-    let index = type_a * num_types + type_b;
+
+// Fonction pour obtenir la force entre deux types de particules d'une simulation
+fn get_force_between_types(sim_index: u32, type_a: u32, type_b: u32) -> f32 {
+    let index = sim_index * num_types * num_types + type_a * num_types + type_b;
     return force_matrix[index];
 }
 
+fn get_type_properties(sim_index: u32, particle_type: u32) -> TypeProperties {
+    return type_properties[sim_index * num_types + particle_type];
+}
+
+// Noyau classique de Particle Life, `r` étant la distance normalisée par la portée
+fn particle_life_kernel(r: f32, attraction: f32) -> f32 {
+    let beta = params.beta;
+    if (r < beta) {
+        // Répulsion universelle, quel que soit le type
+        return r / beta - 1.0;
+    }
+    if (r < 1.0) {
+        // Rampe triangulaire pondérée par la matrice, maximale à mi-chemin entre beta et 1
+        return attraction * (1.0 - abs(2.0 * r - 1.0 - beta) / (1.0 - beta));
+    }
+    return 0.0;
+}
+
 // Fonction pour calculer la force entre deux particules
-fn calculate_force(pos_a: vec3<f32>, pos_b: vec3<f32>, type_a: u32, type_b: u32) -> vec3<f32> {
-    let diff = pos_b - pos_a;
+fn calculate_force(pos_a: vec3<f32>, pos_b: vec3<f32>, type_a: u32, type_b: u32, sim_index: u32) -> vec3<f32> {
+    let diff = minimum_image(pos_b - pos_a);
     let distance = length(diff);
+    let force_strength = get_force_between_types(sim_index, type_a, type_b);
+    // Portée propre au type qui subit la force
+    let range = params.max_force_range * get_type_properties(sim_index, type_a).radius;
+
+    if (params.force_model == FORCE_MODEL_PARTICLE_LIFE) {
+        if (distance <= 0.0 || distance >= range) {
+            return vec3<f32>(0.0, 0.0, 0.0);
+        }
+
+        let kernel = particle_life_kernel(distance / range, force_strength);
+        return (diff / distance) * kernel * range * params.force_scale;
+    }
 
     // Évite la division par zéro et les forces trop importantes
-    if (distance < MIN_DISTANCE || distance > MAX_DISTANCE) {
+    if (distance < params.min_distance || distance > range) {
         return vec3<f32>(0.0, 0.0, 0.0);
     }
 
     let direction = normalize(diff);
-    let force_strength = get_force_between_types(type_a, type_b);
 
     // Force inversement proportionnelle au carré de la distance
-    let force_magnitude = force_strength / (distance * distance);
+    let force_magnitude = force_strength * params.force_scale / (distance * distance);
     let clamped_force = clamp(force_magnitude, -MAX_FORCE, MAX_FORCE);
 
     return direction * clamped_force;
 }
 
-// Fonction pour appliquer les limites du monde (rebonds)
-fn apply_world_bounds(pos: vec3<f32>, vel: vec3<f32>) -> vec3<f32> {
-    var new_vel = vel;
-    let half_size = world_size * 0.5;
+// Doit rester identique à `cell_coords` dans grid_count.wgsl
+fn cell_coords(pos: vec3<f32>) -> vec3<i32> {
+    let coords = vec3<i32>(floor((pos + vec3<f32>(grid.half_world)) / grid.cell_size));
+    return clamp(coords, vec3<i32>(0), vec3<i32>(i32(grid.grid_dim) - 1));
+}
 
-    // Rebond sur les murs avec atténuation
-    if (pos.x > half_size || pos.x < -half_size) {
-        new_vel.x = -new_vel.x * 0.8;
+// Doit rester identique à `cell_index` dans grid_count.wgsl
+fn cell_index(sim_index: u32, coords: vec3<i32>) -> u32 {
+    let dim = grid.grid_dim;
+    return sim_index * dim * dim * dim + (u32(coords.z) * dim + u32(coords.y)) * dim + u32(coords.x);
+}
+
+// En monde torique, ramène l'écart vers l'image la plus proche
+fn minimum_image(diff: vec3<f32>) -> vec3<f32> {
+    if (params.boundary_mode != BOUNDARY_WRAP) {
+        return diff;
     }
-    if (pos.y > half_size || pos.y < -half_size) {
-        new_vel.y = -new_vel.y * 0.8;
+    return diff - world_size * round(diff / world_size);
+}
+
+// Force exercée par les bords : mur mou ou rappel vers le centre
+fn boundary_force(pos: vec3<f32>) -> vec3<f32> {
+    if (params.boundary_mode == BOUNDARY_SOFT_WALL) {
+        // Potentiel quadratique : la force croît linéairement avec la pénétration dans le mur
+        let inner = world_size * (0.5 - WALL_THICKNESS_RATIO);
+        let penetration = max(abs(pos) - vec3<f32>(inner), vec3<f32>(0.0));
+        return -sign(pos) * penetration * params.wall_stiffness;
     }
-    if (pos.z > half_size || pos.z < -half_size) {
-        new_vel.z = -new_vel.z * 0.8;
+    if (params.boundary_mode == BOUNDARY_UNBOUNDED) {
+        return -pos * params.centering_strength;
     }
+    return vec3<f32>(0.0, 0.0, 0.0);
+}
 
-    return new_vel;
+// Ramène la position dans le cube [-half_size, half_size[ en monde torique
+fn wrap_position(pos: vec3<f32>) -> vec3<f32> {
+    let half_size = world_size * 0.5;
+    return pos - world_size * floor((pos + vec3<f32>(half_size)) / world_size);
+}
+
+// Murs rigides : la position est bornée et la vitesse normale inversée
+fn reflect_on_walls(pos: ptr<function, vec3<f32>>, vel: ptr<function, vec3<f32>>) {
+    let half_size = world_size * 0.5;
+    for (var axis = 0; axis < 3; axis++) {
+        if ((*pos)[axis] > half_size) {
+            (*pos)[axis] = half_size;
+            (*vel)[axis] = -abs((*vel)[axis]) * params.restitution;
+        }
+        if ((*pos)[axis] < -half_size) {
+            (*pos)[axis] = -half_size;
+            (*vel)[axis] = abs((*vel)[axis]) * params.restitution;
+        }
+    }
 }
 
 @compute @workgroup_size(64, 1, 1)
-fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
-    let particle_index = global_id.x;
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    // Dispatch en 2D au-delà de la limite de groupes par dimension (voir `dispatch_size`)
+    let particle_index = global_id.y * num_workgroups.x * 64u + global_id.x;
 
     if (particle_index >= num_particles) {
         return;
@@ -84,74 +213,97 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let current_pos = positions[particle_index].xyz;
     let current_type = u32(positions[particle_index].w);
     let current_vel = velocities[particle_index].xyz;
+    let sim_index = u32(velocities[particle_index].w);
+    let range = sim_ranges[sim_index];
 
     var total_force = vec3<f32>(0.0, 0.0, 0.0);
-    var interaction_count = 0u;
 
-    // Parcourt toutes les autres particules
-    for (var i: u32 = 0u; i < num_particles; i++) {
-        if (i == particle_index) {
-            continue;
+    if (grid.enabled != 0u) {
+        // Les cellules font au moins la portée : seules les 27 voisines peuvent interagir.
+        // La grille de chaque simulation est distincte, aucune particule étrangère n'y figure.
+        let dim = i32(grid.grid_dim);
+        let home = cell_coords(current_pos);
+        var lower = max(home - vec3<i32>(1), vec3<i32>(0));
+        var upper = min(home + vec3<i32>(1), vec3<i32>(dim - 1));
+
+        // En monde torique, les voisines débordent de l'autre côté ; sous 3 cellules
+        // par axe, on parcourt tout l'axe pour ne jamais visiter une cellule deux fois
+        let wrap = params.boundary_mode == BOUNDARY_WRAP;
+        if (wrap) {
+            let span = min(dim, 3);
+            lower = select(vec3<i32>(0), home - vec3<i32>(1), vec3<bool>(dim >= 3));
+            upper = lower + vec3<i32>(span - 1);
         }
 
-        let other_pos = positions[i].xyz;
-        let other_type = u32(positions[i].w);
-        let distance = length(other_pos - current_pos);
+        for (var z = lower.z; z <= upper.z; z++) {
+            for (var y = lower.y; y <= upper.y; y++) {
+                for (var x = lower.x; x <= upper.x; x++) {
+                    let coords = (vec3<i32>(x, y, z) % dim + vec3<i32>(dim)) % dim;
+                    let cell = cell_index(sim_index, coords);
+                    for (var k = cell_starts[cell]; k < cell_starts[cell + 1u]; k++) {
+                        let i = sorted_indices[k];
+                        if (i == particle_index) {
+                            continue;
+                        }
 
-        // Compte les interactions dans la portée
-        if (distance >= MIN_DISTANCE && distance <= MAX_DISTANCE) {
-            interaction_count++;
-            let force = calculate_force(current_pos, other_pos, current_type, other_type);
-            total_force += force;
+                        let other_pos = positions[i].xyz;
+                        let other_type = u32(positions[i].w);
+                        total_force += calculate_force(current_pos, other_pos, current_type, other_type, sim_index);
+                    }
+                }
+            }
+        }
+    } else {
+        // Parcourt uniquement les particules de la même simulation
+        for (var i: u32 = range.x; i < range.x + range.y; i++) {
+            if (i == particle_index) {
+                continue;
+            }
+
+            let other_pos = positions[i].xyz;
+            let other_type = u32(positions[i].w);
+
+            // calculate_force renvoie zéro hors de la portée du modèle choisi
+            total_force += calculate_force(current_pos, other_pos, current_type, other_type, sim_index);
         }
     }
 
+    total_force += boundary_force(current_pos);
+
+    // En 2D, l'axe z est neutralisé : forces, vitesses et positions restent dans le plan
+    let plane = vec3<f32>(1.0, 1.0, select(0.0, 1.0, params.dimensions == 3u));
+    total_force *= plane;
+
+    let properties = get_type_properties(sim_index, current_type);
+
     // Appliquer la physique seulement si il y a des forces
     var new_vel = current_vel;
     if (length(total_force) > 0.01) {
-        new_vel = current_vel + total_force * dt;
+        new_vel = current_vel + total_force / properties.mass * dt;
     }
 
     // Applique la friction
-    new_vel *= FRICTION;
+    new_vel *= pow(params.friction, properties.damping) * plane;
 
     // Limite la vélocité maximale
+    let max_velocity = params.max_velocity * properties.max_speed;
     let vel_magnitude = length(new_vel);
-    if (vel_magnitude > MAX_VELOCITY) {
-        new_vel = normalize(new_vel) * MAX_VELOCITY;
+    if (vel_magnitude > max_velocity) {
+        new_vel = normalize(new_vel) * max_velocity;
     }
 
     // Calcule la nouvelle position
     var new_pos = current_pos + new_vel * dt;
 
-    // Applique les limites du monde et ajuste la vélocité si nécessaire
-    let half_size = world_size * 0.5;
-    if (new_pos.x > half_size) {
-        new_pos.x = half_size;
-        new_vel.x = -abs(new_vel.x) * 0.8;
-    }
-    if (new_pos.x < -half_size) {
-        new_pos.x = -half_size;
-        new_vel.x = abs(new_vel.x) * 0.8;
-    }
-    if (new_pos.y > half_size) {
-        new_pos.y = half_size;
-        new_vel.y = -abs(new_vel.y) * 0.8;
-    }
-    if (new_pos.y < -half_size) {
-        new_pos.y = -half_size;
-        new_vel.y = abs(new_vel.y) * 0.8;
-    }
-    if (new_pos.z > half_size) {
-        new_pos.z = half_size;
-        new_vel.z = -abs(new_vel.z) * 0.8;
-    }
-    if (new_pos.z < -half_size) {
-        new_pos.z = -half_size;
-        new_vel.z = abs(new_vel.z) * 0.8;
+    // Applique les conditions aux bords
+    if (params.boundary_mode == BOUNDARY_WRAP) {
+        new_pos = wrap_position(new_pos);
+    } else if (params.boundary_mode == BOUNDARY_REFLECT) {
+        reflect_on_walls(&new_pos, &new_vel);
     }
+    new_pos *= plane;
 
     // Écrit les nouveaux états
     new_positions[particle_index] = vec4<f32>(new_pos, f32(current_type));
-    new_velocities[particle_index] = vec4<f32>(new_vel, 0.0);
+    new_velocities[particle_index] = vec4<f32>(new_vel, f32(sim_index));
 }
\ No newline at end of file
@@ -0,0 +1,72 @@
+// Rendu instancié des particules : une instance par particule, positions lues directement
+// dans les buffers de la simulation (voir ParticleRenderPlugin)
+
+#import bevy_render::view::View
+
+@group(0) @binding(0) var<uniform> view: View;
+
+// Dernières positions (x, y, z, type)
+@group(1) @binding(0) var<storage, read> positions: array<vec4<f32>>;
+
+// Positions d'avant le dernier lot de pas
+@group(1) @binding(1) var<storage, read> previous_positions: array<vec4<f32>>;
+
+// Couleurs de chaque type (voir ParticleTypesConfig)
+struct TypeColor {
+    base: vec4<f32>,
+    emissive: vec4<f32>,
+}
+@group(1) @binding(2) var<storage, read> type_colors: array<TypeColor>;
+
+// Paramètres de rendu (voir ParticleRenderParams côté Rust)
+struct RenderParams {
+    blend: f32,
+    half_world: f32,
+}
+@group(1) @binding(3) var<uniform> render_params: RenderParams;
+
+// Éclairage fixe, proche de la lumière directionnelle de la scène
+const LIGHT_DIRECTION: vec3<f32> = vec3<f32>(0.42, 0.74, 0.52);
+const AMBIENT: f32 = 0.35;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+    @location(1) base_color: vec4<f32>,
+    @location(2) emissive: vec4<f32>,
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    // L'instance est l'index global de la particule : le draw commence à l'offset de sa simulation
+    let current = positions[vertex.instance_index];
+    let previous = previous_positions[vertex.instance_index].xyz;
+
+    // Une particule repliée de l'autre côté du monde saute directement à sa position
+    var center = current.xyz;
+    if (all(abs(current.xyz - previous) < vec3<f32>(render_params.half_world))) {
+        center = mix(previous, current.xyz, render_params.blend);
+    }
+
+    let color = type_colors[u32(current.w) % arrayLength(&type_colors)];
+
+    var out: VertexOutput;
+    out.clip_position = view.clip_from_world * vec4<f32>(vertex.position + center, 1.0);
+    out.normal = vertex.normal;
+    out.base_color = color.base;
+    out.emissive = color.emissive;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let diffuse = max(dot(normalize(in.normal), normalize(LIGHT_DIRECTION)), 0.0);
+    let lit = in.base_color.rgb * (AMBIENT + (1.0 - AMBIENT) * diffuse);
+    return vec4<f32>(lit + in.emissive.rgb, in.base_color.a);
+}
@@ -0,0 +1,24 @@
+// Conserve les positions d'avant le lot de pas, pour interpoler le rendu (voir particle_render.wgsl)
+
+@group(0) @binding(0) var<uniform> num_particles: u32;
+
+// Positions des particules (x, y, z, type)
+@group(0) @binding(1) var<storage, read> positions: array<vec4<f32>>;
+
+// Copie lue par le vertex shader
+@group(0) @binding(2) var<storage, read_write> previous_positions: array<vec4<f32>>;
+
+@compute @workgroup_size(64, 1, 1)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    // Dispatch en 2D au-delà de la limite de groupes par dimension (voir `dispatch_size`)
+    let particle_index = global_id.y * num_workgroups.x * 64u + global_id.x;
+
+    if (particle_index >= num_particles) {
+        return;
+    }
+
+    previous_positions[particle_index] = positions[particle_index];
+}
@@ -1,16 +1,15 @@
 use bevy::prelude::*;
+use bevy::render::extract_component::ExtractComponent;
 
-/// Composant pour une particule de vie
-#[derive(Component, Debug, Clone)]
-pub struct LifeParticle {
-    pub particle_type: u32,
-    pub index: u32, // Index dans les buffers GPU
-}
-
-impl LifeParticle {
-    pub fn new(particle_type: u32, index: u32) -> Self {
-        Self { particle_type, index }
-    }
+/// Particules d'une simulation, dessinées en un seul appel instancié qui lit leurs positions
+/// directement dans les buffers du backend (voir `ParticleRenderPlugin`)
+#[derive(Component, Clone, ExtractComponent)]
+pub struct ParticleInstances {
+    pub simulation_id: u32,
+    /// Plage de la simulation dans les buffers de positions
+    pub offset: u32,
+    pub count: u32,
+    pub mesh: Handle<Mesh>,
 }
 
 /// Marqueur pour identifier le parent des particules
@@ -3,15 +3,18 @@ use bevy::prelude::*;
 #[derive(Component)]
 pub struct Simulation {
     pub id: u32,
+    /// Score de l'époque en cours, combinaison pondérée des objectifs (voir `evaluate_fitness`)
     pub score: f32,
+    /// Nourriture mangée depuis la dernière évaluation
+    pub food_eaten: f32,
 }
 
 impl Simulation {
     pub fn new(id: u32) -> Self {
-        Self { id, score: 0.0 }
+        Self { id, score: 0.0, food_eaten: 0.0 }
     }
 
-    pub fn add_score(&mut self, points: f32) {
-        self.score += points;
+    pub fn eat(&mut self, value: f32) {
+        self.food_eaten += value;
     }
-}
\ No newline at end of file
+}
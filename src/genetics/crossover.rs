@@ -0,0 +1,203 @@
+use rand::{Rng, RngCore};
+
+use crate::genetics::genome::Genome;
+use crate::resources::physics_params::TypeProperties;
+use crate::resources::simulation_config::{CrossoverMethod, SimulationConfig};
+
+/// Croisement de deux génomes de même nombre de types.
+///
+/// `child` part d'une copie du premier parent et reçoit une partie des gènes de `other` ;
+/// les bornes sont rétablies ensuite par `Genome::clamp`.
+pub trait CrossoverOperator: Send + Sync {
+    fn cross(&self, child: &mut Genome, other: &Genome, rng: &mut dyn RngCore);
+}
+
+/// Opérateur décrit par la configuration
+pub fn crossover_operator(config: &SimulationConfig) -> Box<dyn CrossoverOperator> {
+    match config.crossover {
+        CrossoverMethod::Uniform => Box::new(Uniform),
+        CrossoverMethod::RowSwap => Box::new(RowSwap),
+        CrossoverMethod::SinglePoint => Box::new(SinglePoint),
+        CrossoverMethod::Blend => Box::new(Blend { alpha: config.blend_alpha }),
+    }
+}
+
+/// Chaque case de la matrice et chaque type viennent de l'un ou l'autre parent
+pub struct Uniform;
+
+impl CrossoverOperator for Uniform {
+    fn cross(&self, child: &mut Genome, other: &Genome, rng: &mut dyn RngCore) {
+        for (force, &other_force) in child.force_matrix.iter_mut().zip(&other.force_matrix) {
+            if rng.random::<bool>() {
+                *force = other_force;
+            }
+        }
+        for (properties, &other_properties) in child.type_properties.iter_mut().zip(&other.type_properties) {
+            if rng.random::<bool>() {
+                *properties = other_properties;
+            }
+        }
+    }
+}
+
+/// Chaque ligne vient entière de l'un ou l'autre parent : le type hérite de la façon dont
+/// un parent voit tous les autres, avec ses propriétés
+pub struct RowSwap;
+
+impl CrossoverOperator for RowSwap {
+    fn cross(&self, child: &mut Genome, other: &Genome, rng: &mut dyn RngCore) {
+        let n = child.num_types as usize;
+        for row in 0..n {
+            if rng.random::<bool>() {
+                child.force_matrix[row * n..(row + 1) * n].copy_from_slice(&other.force_matrix[row * n..(row + 1) * n]);
+                child.type_properties[row] = other.type_properties[row];
+            }
+        }
+    }
+}
+
+/// Les cases qui suivent un point de coupure, dans l'ordre des lignes, viennent de l'autre
+/// parent ; un type suit sa ligne si elle commence après la coupure
+pub struct SinglePoint;
+
+impl CrossoverOperator for SinglePoint {
+    fn cross(&self, child: &mut Genome, other: &Genome, rng: &mut dyn RngCore) {
+        let len = child.force_matrix.len();
+        if len < 2 {
+            return;
+        }
+
+        let cut = rng.random_range(1..len);
+        child.force_matrix[cut..].copy_from_slice(&other.force_matrix[cut..]);
+
+        let n = child.num_types as usize;
+        let first_type = cut.div_ceil(n);
+        child.type_properties[first_type..].copy_from_slice(&other.type_properties[first_type..]);
+    }
+}
+
+/// BLX-α : chaque gène est tiré dans l'intervalle des deux parents élargi de `alpha` fois
+/// son étendue de chaque côté
+pub struct Blend {
+    pub alpha: f32,
+}
+
+impl Blend {
+    fn blend(&self, a: f32, b: f32, rng: &mut dyn RngCore) -> f32 {
+        let (low, high) = (a.min(b), a.max(b));
+        let margin = (high - low) * self.alpha.max(0.0);
+        low - margin + rng.random::<f32>() * (high - low + 2.0 * margin)
+    }
+}
+
+impl CrossoverOperator for Blend {
+    fn cross(&self, child: &mut Genome, other: &Genome, rng: &mut dyn RngCore) {
+        for (force, &other_force) in child.force_matrix.iter_mut().zip(&other.force_matrix) {
+            *force = self.blend(*force, other_force, rng);
+        }
+
+        for (properties, other_properties) in child.type_properties.iter_mut().zip(&other.type_properties) {
+            let TypeProperties { mass, radius, max_speed, damping } = properties;
+            let others = [other_properties.mass, other_properties.radius, other_properties.max_speed, other_properties.damping];
+            for (value, other_value) in [mass, radius, max_speed, damping].into_iter().zip(others) {
+                *value = self.blend(*value, other_value, rng);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::globals::MAX_FORCE_WEIGHT;
+
+    const NUM_TYPES: u32 = 6;
+
+    /// Deux parents faciles à distinguer : forces à 1 et noyaux contre forces à -1 et électrons
+    fn parents() -> (Genome, Genome) {
+        let mut a = Genome::new(NUM_TYPES);
+        a.force_matrix.fill(1.0);
+        a.type_properties.fill(TypeProperties::NUCLEUS);
+        let mut b = Genome::new(NUM_TYPES);
+        b.force_matrix.fill(-1.0);
+        b.type_properties.fill(TypeProperties::ELECTRON);
+        (a, b)
+    }
+
+    fn cross(operator: &dyn CrossoverOperator, seed: u64) -> Genome {
+        let (mut child, other) = parents();
+        operator.cross(&mut child, &other, &mut StdRng::seed_from_u64(seed));
+        child
+    }
+
+    #[test]
+    fn uniform_takes_each_gene_from_one_parent() {
+        let child = cross(&Uniform, 1);
+        assert!(child.force_matrix.iter().all(|&force| force == 1.0 || force == -1.0));
+        assert!(child.force_matrix.contains(&1.0) && child.force_matrix.contains(&-1.0));
+        assert!(child.type_properties.iter().all(|&p| p == TypeProperties::NUCLEUS || p == TypeProperties::ELECTRON));
+        assert!(child.type_properties.contains(&TypeProperties::NUCLEUS) && child.type_properties.contains(&TypeProperties::ELECTRON));
+        assert_eq!(child, cross(&Uniform, 1));
+    }
+
+    #[test]
+    fn row_swap_moves_whole_rows_with_their_type() {
+        let child = cross(&RowSwap, 1);
+        let n = NUM_TYPES as usize;
+        let mut sources = Vec::new();
+        for (row, forces) in child.force_matrix.chunks(n).enumerate() {
+            assert!(forces.iter().all(|&force| force == forces[0]), "ligne {} mélangée : {:?}", row, forces);
+            let expected = if forces[0] == 1.0 { TypeProperties::NUCLEUS } else { TypeProperties::ELECTRON };
+            assert_eq!(child.type_properties[row], expected);
+            sources.push(forces[0]);
+        }
+        assert!(sources.contains(&1.0) && sources.contains(&-1.0));
+    }
+
+    #[test]
+    fn single_point_cut_lands_inside_the_matrix() {
+        let n = NUM_TYPES as usize;
+        for seed in 0..200 {
+            let child = cross(&SinglePoint, seed);
+            let cut = child.force_matrix.iter().position(|&force| force == -1.0).unwrap();
+            assert!((1..n * n).contains(&cut), "coupure {} hors de la matrice", cut);
+            assert!(child.force_matrix[cut..].iter().all(|&force| force == -1.0));
+
+            for (index, &properties) in child.type_properties.iter().enumerate() {
+                let from_other = index * n >= cut;
+                let expected = if from_other { TypeProperties::ELECTRON } else { TypeProperties::NUCLEUS };
+                assert_eq!(properties, expected, "type {} avec la coupure {}", index, cut);
+            }
+        }
+    }
+
+    #[test]
+    fn blend_stays_within_the_expanded_bounds() {
+        let alpha = 0.5;
+        let (mut child, mut other) = parents();
+        child.force_matrix.fill(MAX_FORCE_WEIGHT - 0.5);
+        other.force_matrix.fill(MAX_FORCE_WEIGHT);
+        let parent = child.clone();
+        Blend { alpha }.cross(&mut child, &other, &mut StdRng::seed_from_u64(1));
+
+        for (i, &force) in child.force_matrix.iter().enumerate() {
+            let (low, high) = (parent.force_matrix[i], other.force_matrix[i]);
+            let margin = (high - low) * alpha;
+            assert!(force >= low - margin && force <= high + margin, "force {} hors de [{}, {}]", force, low - margin, high + margin);
+        }
+        // L'intervalle élargi dépasse la borne de la matrice : `Genome::clamp` doit la rétablir
+        assert!(child.force_matrix.iter().any(|&force| force > MAX_FORCE_WEIGHT));
+        let mass_margin = (TypeProperties::NUCLEUS.mass - TypeProperties::ELECTRON.mass) * alpha;
+        for properties in &child.type_properties {
+            assert!(properties.mass >= TypeProperties::ELECTRON.mass - mass_margin);
+            assert!(properties.mass <= TypeProperties::NUCLEUS.mass + mass_margin);
+        }
+
+        child.clamp();
+        assert!(child.force_matrix.iter().all(|force| force.abs() <= MAX_FORCE_WEIGHT));
+        assert!(child.type_properties.iter().all(|&properties| properties == properties.clamped()));
+    }
+}
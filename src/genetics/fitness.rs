@@ -0,0 +1,375 @@
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+use crate::globals::CLUSTER_MIN_PARTICLES;
+use crate::resources::physics_params::TypeProperties;
+use crate::resources::simulation_config::{BoundaryMode, FitnessObjective, SimulationConfig};
+
+/// État publié d'une simulation, tel que l'observent les fonctions de fitness
+pub struct FitnessSample<'a> {
+    /// (x, y, z, type)
+    pub positions: &'a [[f32; 4]],
+    /// (x, y, z, index de simulation)
+    pub velocities: &'a [[f32; 4]],
+    pub type_properties: &'a [TypeProperties],
+    /// Nourriture mangée depuis l'observation précédente
+    pub food_eaten: f32,
+}
+
+/// Objectif évalué sur une simulation pendant une époque.
+///
+/// Une instance suit une seule simulation : `observe` reçoit chaque nouvel état publié,
+/// `score` donne la valeur de l'époque en cours. Une nouvelle instance est créée à chaque époque.
+pub trait FitnessFunction: Send + Sync {
+    fn observe(&mut self, sample: &FitnessSample);
+    fn score(&self) -> f32;
+}
+
+/// Objectifs de poids non nul, avec leur poids
+pub fn fitness_functions(config: &SimulationConfig) -> Vec<(f32, Box<dyn FitnessFunction>)> {
+    let world = WorldGeometry::from(config);
+    FitnessObjective::ALL
+        .into_iter()
+        .map(|objective| (config.fitness_weights.weight(objective), objective))
+        .filter(|&(weight, _)| weight != 0.0)
+        .map(|(weight, objective)| {
+            let function: Box<dyn FitnessFunction> = match objective {
+                FitnessObjective::Food => Box::new(FoodEaten::default()),
+                FitnessObjective::KineticEnergy => Box::new(KineticEnergy::default()),
+                FitnessObjective::Clusters => Box::new(MeanClusterCount::new(world, config.max_force_range)),
+                FitnessObjective::Spread => Box::new(Spread::new(world)),
+                FitnessObjective::Travel => Box::new(CenterOfMassTravel::new(world)),
+            };
+            (weight, function)
+        })
+        .collect()
+}
+
+/// Somme des valeurs de la nourriture mangée
+#[derive(Default)]
+pub struct FoodEaten {
+    total: f32,
+}
+
+impl FitnessFunction for FoodEaten {
+    fn observe(&mut self, sample: &FitnessSample) {
+        self.total += sample.food_eaten;
+    }
+
+    fn score(&self) -> f32 {
+        self.total
+    }
+}
+
+/// Moyenne sur l'époque de l'énergie cinétique moyenne ½ m v² d'une particule
+#[derive(Default)]
+pub struct KineticEnergy {
+    mean: RunningMean,
+}
+
+impl FitnessFunction for KineticEnergy {
+    fn observe(&mut self, sample: &FitnessSample) {
+        if sample.positions.is_empty() {
+            return;
+        }
+
+        let total: f32 = sample.positions.iter().zip(sample.velocities)
+            .map(|(position, velocity)| {
+                let mass = sample.type_properties.get(position[3] as usize).map_or(1.0, |properties| properties.mass);
+                0.5 * mass * Vec3::new(velocity[0], velocity[1], velocity[2]).length_squared()
+            })
+            .sum();
+        self.mean.push(total / sample.positions.len() as f32);
+    }
+
+    fn score(&self) -> f32 {
+        self.mean.value()
+    }
+}
+
+/// Nombre moyen d'amas sur l'époque, recompté à chaque état publié sans suivre les amas d'un
+/// état à l'autre. Un amas est un ensemble connexe (26-voisinage) de cellules de côté
+/// `cell_size` contenant chacune au moins `CLUSTER_MIN_PARTICLES` particules.
+pub struct MeanClusterCount {
+    world: WorldGeometry,
+    cell_size: f32,
+    /// Cellules par axe sur le tore, dont les indices se replient ; 0 sans repli
+    cells_per_side: i32,
+    mean: RunningMean,
+}
+
+impl MeanClusterCount {
+    pub fn new(world: WorldGeometry, cell_size: f32) -> Self {
+        let cell_size = cell_size.max(f32::EPSILON);
+        if !world.wrap {
+            return Self { world, cell_size, cells_per_side: 0, mean: RunningMean::default() };
+        }
+
+        // Les cellules pavent exactement le tore : un peu plus grandes que `cell_size`, une seule
+        // par axe si le monde est plus petit
+        let cells_per_side = ((world.size / cell_size).floor() as i32).max(1);
+        Self {
+            world,
+            cell_size: world.size / cells_per_side as f32,
+            cells_per_side,
+            mean: RunningMean::default(),
+        }
+    }
+
+    fn wrap_cell(&self, cell: IVec3) -> IVec3 {
+        if self.cells_per_side == 0 {
+            return cell;
+        }
+        cell.rem_euclid(IVec3::splat(self.cells_per_side))
+    }
+
+    fn count_clusters(&self, positions: &[[f32; 4]]) -> usize {
+        let half_world = self.world.size * 0.5;
+        let mut cells: HashMap<IVec3, usize> = HashMap::new();
+        for position in positions {
+            let cell = ((point(position) + half_world) / self.cell_size).floor().as_ivec3();
+            *cells.entry(self.wrap_cell(cell)).or_default() += 1;
+        }
+
+        let mut dense: HashSet<IVec3> = cells.into_iter()
+            .filter(|&(_, count)| count >= CLUSTER_MIN_PARTICLES)
+            .map(|(cell, _)| cell)
+            .collect();
+
+        // Composantes connexes par parcours en profondeur
+        let mut clusters = 0;
+        while let Some(&start) = dense.iter().next() {
+            dense.remove(&start);
+            clusters += 1;
+
+            let mut stack = vec![start];
+            while let Some(cell) = stack.pop() {
+                for z in -1..=1 {
+                    for y in -1..=1 {
+                        for x in -1..=1 {
+                            let neighbor = self.wrap_cell(cell + IVec3::new(x, y, z));
+                            if dense.remove(&neighbor) {
+                                stack.push(neighbor);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        clusters
+    }
+}
+
+impl FitnessFunction for MeanClusterCount {
+    fn observe(&mut self, sample: &FitnessSample) {
+        if sample.positions.is_empty() {
+            return;
+        }
+        let clusters = self.count_clusters(sample.positions);
+        self.mean.push(clusters as f32);
+    }
+
+    fn score(&self) -> f32 {
+        self.mean.value()
+    }
+}
+
+/// Moyenne sur l'époque de la distance quadratique moyenne au centre de masse
+pub struct Spread {
+    world: WorldGeometry,
+    mean: RunningMean,
+}
+
+impl Spread {
+    pub fn new(world: WorldGeometry) -> Self {
+        Self { world, mean: RunningMean::default() }
+    }
+}
+
+impl FitnessFunction for Spread {
+    fn observe(&mut self, sample: &FitnessSample) {
+        let Some(center) = self.world.center_of_mass(sample.positions) else {
+            return;
+        };
+
+        let squared: f32 = sample.positions.iter()
+            .map(|position| self.world.minimum_image(point(position) - center).length_squared())
+            .sum();
+        self.mean.push((squared / sample.positions.len() as f32).sqrt());
+    }
+
+    fn score(&self) -> f32 {
+        self.mean.value()
+    }
+}
+
+/// Chemin parcouru par le centre de masse (non pondéré) au fil des états publiés.
+/// Le déplacement du centre est la moyenne des déplacements des particules, chacun pris en
+/// image minimale : une particule qui traverse un bord du monde torique ne fait pas sauter le centre.
+pub struct CenterOfMassTravel {
+    world: WorldGeometry,
+    previous: Vec<Vec3>,
+    distance: f32,
+}
+
+impl CenterOfMassTravel {
+    pub fn new(world: WorldGeometry) -> Self {
+        Self { world, previous: Vec::new(), distance: 0.0 }
+    }
+}
+
+impl FitnessFunction for CenterOfMassTravel {
+    fn observe(&mut self, sample: &FitnessSample) {
+        let positions: Vec<Vec3> = sample.positions.iter().map(point).collect();
+
+        if !positions.is_empty() && positions.len() == self.previous.len() {
+            let displacement: Vec3 = positions.iter().zip(&self.previous)
+                .map(|(&position, &previous)| self.world.minimum_image(position - previous))
+                .sum();
+            self.distance += (displacement / positions.len() as f32).length();
+        }
+        self.previous = positions;
+    }
+
+    fn score(&self) -> f32 {
+        self.distance
+    }
+}
+
+/// Taille et bords du monde, pour mesurer les écarts comme le noyau de forces
+#[derive(Clone, Copy)]
+pub struct WorldGeometry {
+    pub size: f32,
+    pub wrap: bool,
+}
+
+impl From<&SimulationConfig> for WorldGeometry {
+    fn from(config: &SimulationConfig) -> Self {
+        Self {
+            size: config.world_size,
+            wrap: config.boundary_mode == BoundaryMode::Wrap,
+        }
+    }
+}
+
+impl WorldGeometry {
+    /// Même convention que `ForceField::minimum_image`
+    pub fn minimum_image(&self, diff: Vec3) -> Vec3 {
+        if !self.wrap {
+            return diff;
+        }
+        diff - self.size * (diff / self.size).round()
+    }
+
+    /// Centre de masse ; sur le tore, moyenne circulaire de chaque coordonnée vue comme un angle
+    pub fn center_of_mass(&self, positions: &[[f32; 4]]) -> Option<Vec3> {
+        if positions.is_empty() {
+            return None;
+        }
+        if !self.wrap {
+            let sum: Vec3 = positions.iter().map(point).sum();
+            return Some(sum / positions.len() as f32);
+        }
+
+        let to_angle = std::f32::consts::TAU / self.size;
+        let (cos, sin) = positions.iter()
+            .map(|position| point(position) * to_angle)
+            .fold((Vec3::ZERO, Vec3::ZERO), |(cos, sin), angle| {
+                (cos + Vec3::new(angle.x.cos(), angle.y.cos(), angle.z.cos()),
+                 sin + Vec3::new(angle.x.sin(), angle.y.sin(), angle.z.sin()))
+            });
+        let angle = Vec3::new(sin.x.atan2(cos.x), sin.y.atan2(cos.y), sin.z.atan2(cos.z));
+        Some(angle / to_angle)
+    }
+}
+
+fn point(position: &[f32; 4]) -> Vec3 {
+    Vec3::new(position[0], position[1], position[2])
+}
+
+#[derive(Default)]
+struct RunningMean {
+    sum: f32,
+    count: u32,
+}
+
+impl RunningMean {
+    fn push(&mut self, value: f32) {
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn value(&self) -> f32 {
+        if self.count == 0 { 0.0 } else { self.sum / self.count as f32 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORLD: WorldGeometry = WorldGeometry { size: 50.0, wrap: true };
+    const WALLS: WorldGeometry = WorldGeometry { size: 50.0, wrap: false };
+
+    fn sample(positions: &[[f32; 4]]) -> FitnessSample<'_> {
+        FitnessSample { positions, velocities: &[], type_properties: &[], food_eaten: 0.0 }
+    }
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "{} au lieu de {}", actual, expected);
+    }
+
+    #[test]
+    fn travel_follows_particles_across_the_wrapped_edge() {
+        // Chaque particule avance d'une unité vers +x ; la première traverse le bord
+        let before = [[24.5, 0.0, 0.0, 0.0], [10.0, 3.0, 0.0, 0.0]];
+        let after = [[-24.5, 0.0, 0.0, 0.0], [11.0, 3.0, 0.0, 0.0]];
+
+        let mut travel = CenterOfMassTravel::new(WORLD);
+        travel.observe(&sample(&before));
+        travel.observe(&sample(&after));
+        assert_near(travel.score(), 1.0);
+
+        // Sans repli, les positions brutes font sauter le centre de (49 - 1) / 2
+        let mut travel = CenterOfMassTravel::new(WALLS);
+        travel.observe(&sample(&before));
+        travel.observe(&sample(&after));
+        assert_near(travel.score(), 24.0);
+    }
+
+    #[test]
+    fn spread_measures_clusters_split_by_the_wrapped_edge() {
+        // Deux particules à une unité l'une de l'autre, de part et d'autre du bord
+        let positions = [[24.5, 5.0, 0.0, 0.0], [-24.5, 5.0, 0.0, 0.0]];
+
+        let center = WORLD.center_of_mass(&positions).unwrap();
+        assert_near(WORLD.minimum_image(center - Vec3::new(25.0, 5.0, 0.0)).length(), 0.0);
+
+        let mut spread = Spread::new(WORLD);
+        spread.observe(&sample(&positions));
+        assert_near(spread.score(), 0.5);
+
+        let mut spread = Spread::new(WALLS);
+        spread.observe(&sample(&positions));
+        assert_near(spread.score(), 24.5);
+    }
+
+    #[test]
+    fn clusters_split_by_the_wrapped_edge_count_once() {
+        // Deux cellules denses de part et d'autre du bord, voisines sur le tore
+        let mut positions = Vec::new();
+        for i in 0..CLUSTER_MIN_PARTICLES {
+            let y = 5.0 + i as f32 * 0.1;
+            positions.push([24.8, y, 0.0, 0.0]);
+            positions.push([-24.8, y, 0.0, 0.0]);
+        }
+
+        let mut clusters = MeanClusterCount::new(WORLD, 10.0);
+        clusters.observe(&sample(&positions));
+        assert_near(clusters.score(), 1.0);
+
+        let mut clusters = MeanClusterCount::new(WALLS, 10.0);
+        clusters.observe(&sample(&positions));
+        assert_near(clusters.score(), 2.0);
+    }
+}
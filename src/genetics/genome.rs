@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::globals::MAX_FORCE_WEIGHT;
+use crate::resources::physics_params::TypeProperties;
+
+/// Version du format de fichier, incrémentée à chaque changement incompatible
+pub const GENOME_FORMAT_VERSION: u32 = 1;
+
+/// Règles d'une simulation : forces entre types et propriétés de chaque type.
+/// C'est l'unité sur laquelle opère l'algorithme génétique.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    pub num_types: u32,
+    /// `num_types²` forces, ligne par ligne : `force_matrix[a * num_types + b]` est la force
+    /// exercée par le type `b` sur le type `a`
+    pub force_matrix: Vec<f32>,
+    pub type_properties: Vec<TypeProperties>,
+}
+
+/// Fichier d'un génome : la version précède les données pour pouvoir refuser un format inconnu
+#[derive(Serialize, Deserialize)]
+struct GenomeFile {
+    version: u32,
+    genome: Genome,
+}
+
+/// Lecture préalable de la seule version, avant de désérialiser le reste
+#[derive(Deserialize)]
+struct GenomeFileVersion {
+    version: u32,
+}
+
+impl Genome {
+    /// Forces nulles et propriétés neutres
+    pub fn new(num_types: u32) -> Self {
+        Self {
+            num_types,
+            force_matrix: vec![0.0; (num_types * num_types) as usize],
+            type_properties: vec![TypeProperties::default(); num_types as usize],
+        }
+    }
+
+    pub fn force(&self, type_a: u32, type_b: u32) -> f32 {
+        self.force_matrix.get((type_a * self.num_types + type_b) as usize).copied().unwrap_or(0.0)
+    }
+
+    pub fn set_force(&mut self, type_a: u32, type_b: u32, force: f32) {
+        if let Some(value) = self.force_matrix.get_mut((type_a * self.num_types + type_b) as usize) {
+            *value = force;
+        }
+    }
+
+    /// Ramène forces et propriétés dans leurs bornes
+    pub fn clamp(&mut self) {
+        for force in &mut self.force_matrix {
+            *force = force.clamp(-MAX_FORCE_WEIGHT, MAX_FORCE_WEIGHT);
+        }
+        for properties in &mut self.type_properties {
+            *properties = properties.clamped();
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, String> {
+        let file = GenomeFile {
+            version: GENOME_FORMAT_VERSION,
+            genome: self.clone(),
+        };
+        ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("Sérialisation du génome impossible : {}", e))
+    }
+
+    /// Lit un génome et vérifie sa cohérence ; les valeurs hors bornes sont ramenées dans les bornes
+    pub fn from_ron(text: &str) -> Result<Self, String> {
+        let GenomeFileVersion { version } = ron::from_str(text).map_err(|e| format!("Génome invalide : {}", e))?;
+        if version != GENOME_FORMAT_VERSION {
+            return Err(format!(
+                "Format de génome {} non pris en charge (version attendue : {})",
+                version, GENOME_FORMAT_VERSION
+            ));
+        }
+
+        let GenomeFile { mut genome, .. } = ron::from_str(text).map_err(|e| format!("Génome invalide : {}", e))?;
+        let num_types = genome.num_types as usize;
+        if genome.force_matrix.len() != num_types * num_types {
+            return Err(format!(
+                "Matrice de {} valeurs pour {} types ({} attendues)",
+                genome.force_matrix.len(), num_types, num_types * num_types
+            ));
+        }
+        if genome.type_properties.len() != num_types {
+            return Err(format!(
+                "{} propriétés de type pour {} types",
+                genome.type_properties.len(), num_types
+            ));
+        }
+
+        genome.clamp();
+        Ok(genome)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_ron()?).map_err(|e| format!("Écriture de {} impossible : {}", path.display(), e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Lecture de {} impossible : {}", path.display(), e))?;
+        Self::from_ron(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genome() -> Genome {
+        let mut genome = Genome::new(3);
+        for (i, force) in genome.force_matrix.iter_mut().enumerate() {
+            *force = i as f32 * 0.25 - 1.0;
+        }
+        genome.type_properties[1] = TypeProperties {
+            mass: 2.5,
+            radius: 0.5,
+            max_speed: 1.5,
+            damping: 0.0,
+        };
+        genome
+    }
+
+    #[test]
+    fn save_then_load_gives_the_same_genome() {
+        let path = std::env::temp_dir().join(format!("genome-test-{}.ron", std::process::id()));
+        genome().save(&path).unwrap();
+        let loaded = Genome::load(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.unwrap(), genome());
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let text = genome().to_ron().unwrap();
+        let text = text.replacen(
+            &format!("version: {}", GENOME_FORMAT_VERSION),
+            &format!("version: {}", GENOME_FORMAT_VERSION + 1),
+            1,
+        );
+        assert!(Genome::from_ron(&text).unwrap_err().contains("non pris en charge"));
+    }
+
+    #[test]
+    fn force_matrix_of_the_wrong_size_is_rejected() {
+        let mut genome = genome();
+        genome.force_matrix.pop();
+        assert!(Genome::from_ron(&genome.to_ron().unwrap()).unwrap_err().contains("Matrice"));
+    }
+
+    #[test]
+    fn type_properties_of_the_wrong_size_is_rejected() {
+        let mut genome = genome();
+        genome.type_properties.push(TypeProperties::default());
+        assert!(Genome::from_ron(&genome.to_ron().unwrap()).unwrap_err().contains("propriétés"));
+    }
+}
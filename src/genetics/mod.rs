@@ -0,0 +1,5 @@
+pub mod crossover;
+pub mod fitness;
+pub mod genome;
+pub mod mutation;
+pub mod selection;
@@ -0,0 +1,182 @@
+use rand::{Rng, RngCore};
+
+use crate::genetics::genome::Genome;
+use crate::globals::MAX_FORCE_WEIGHT;
+use crate::resources::physics_params::TypeProperties;
+use crate::resources::simulation_config::{MutationMethod, SimulationConfig};
+
+/// Mutation de la matrice de forces d'un génome ; chaque case (ou chaque type pour les
+/// échanges) est touchée avec la probabilité `rate`
+pub trait MutationOperator: Send + Sync {
+    fn mutate(&self, genome: &mut Genome, rate: f32, rng: &mut dyn RngCore);
+}
+
+/// Opérateur décrit par la configuration
+pub fn mutation_operator(config: &SimulationConfig) -> Box<dyn MutationOperator> {
+    match config.mutation {
+        MutationMethod::Gaussian => Box::new(Gaussian { sigma: config.mutation_sigma }),
+        MutationMethod::Reset => Box::new(Reset),
+        MutationMethod::Swap => Box::new(Swap),
+    }
+}
+
+/// Ajoute un bruit gaussien d'écart type `sigma`
+pub struct Gaussian {
+    pub sigma: f32,
+}
+
+impl MutationOperator for Gaussian {
+    fn mutate(&self, genome: &mut Genome, rate: f32, rng: &mut dyn RngCore) {
+        for force in &mut genome.force_matrix {
+            if rng.random::<f32>() < rate {
+                *force += standard_normal(rng) * self.sigma;
+            }
+        }
+    }
+}
+
+/// Remplace la force par une valeur uniforme dans les bornes de la matrice
+pub struct Reset;
+
+impl MutationOperator for Reset {
+    fn mutate(&self, genome: &mut Genome, rate: f32, rng: &mut dyn RngCore) {
+        for force in &mut genome.force_matrix {
+            if rng.random::<f32>() < rate {
+                *force = rng.random_range(-MAX_FORCE_WEIGHT..=MAX_FORCE_WEIGHT);
+            }
+        }
+    }
+}
+
+/// Échange la ligne (ce que le type ressent) ou la colonne (ce qu'il fait subir) d'un type
+/// avec celle d'un autre type
+pub struct Swap;
+
+impl MutationOperator for Swap {
+    fn mutate(&self, genome: &mut Genome, rate: f32, rng: &mut dyn RngCore) {
+        let n = genome.num_types as usize;
+        if n < 2 {
+            return;
+        }
+
+        for a in 0..n {
+            if rng.random::<f32>() >= rate {
+                continue;
+            }
+            // Autre type que `a`, tiré uniformément
+            let b = (a + rng.random_range(1..n)) % n;
+            if rng.random::<bool>() {
+                for col in 0..n {
+                    genome.force_matrix.swap(a * n + col, b * n + col);
+                }
+            } else {
+                for row in 0..n {
+                    genome.force_matrix.swap(row * n + a, row * n + b);
+                }
+            }
+        }
+    }
+}
+
+/// Les propriétés étant des facteurs, une mutation les multiplie ou les divise par au plus √2,
+/// quel que soit l'opérateur appliqué à la matrice
+pub fn mutate_type_properties(genome: &mut Genome, rate: f32, rng: &mut dyn RngCore) {
+    for properties in &mut genome.type_properties {
+        let TypeProperties { mass, radius, max_speed, damping } = properties;
+        for value in [mass, radius, max_speed, damping] {
+            if rng.random::<f32>() < rate {
+                *value *= 2f32.powf(rng.random_range(-0.5..=0.5));
+            }
+        }
+    }
+}
+
+/// Tirage normal centré réduit (Box-Muller)
+fn standard_normal(rng: &mut dyn RngCore) -> f32 {
+    let u1 = rng.random::<f32>().max(f32::MIN_POSITIVE);
+    let u2 = rng.random::<f32>();
+    (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    const NUM_TYPES: u32 = 40;
+
+    fn genome() -> Genome {
+        let mut genome = Genome::new(NUM_TYPES);
+        for (i, force) in genome.force_matrix.iter_mut().enumerate() {
+            *force = (i % 17) as f32 * 0.2 - 1.6;
+        }
+        genome
+    }
+
+    /// Part des forces modifiées par une mutation au taux `rate`
+    fn mutated_share(operator: &dyn MutationOperator, rate: f32) -> f32 {
+        let original = genome();
+        let mut mutated = original.clone();
+        operator.mutate(&mut mutated, rate, &mut StdRng::seed_from_u64(3));
+        let changed = mutated.force_matrix.iter().zip(&original.force_matrix).filter(|(a, b)| a != b).count();
+        changed as f32 / original.force_matrix.len() as f32
+    }
+
+    #[test]
+    fn zero_rate_leaves_the_genome_untouched() {
+        for operator in [&Gaussian { sigma: 0.5 } as &dyn MutationOperator, &Reset, &Swap] {
+            assert_eq!(mutated_share(operator, 0.0), 0.0);
+        }
+        let mut mutated = genome();
+        mutate_type_properties(&mut mutated, 0.0, &mut StdRng::seed_from_u64(3));
+        assert_eq!(mutated, genome());
+    }
+
+    #[test]
+    fn mutation_touches_about_rate_of_the_forces() {
+        for operator in [&Gaussian { sigma: 0.5 } as &dyn MutationOperator, &Reset] {
+            let share = mutated_share(operator, 0.25);
+            assert!((share - 0.25).abs() < 0.05, "{} des forces modifiées au lieu de 25 %", share);
+        }
+    }
+
+    #[test]
+    fn swap_only_moves_existing_forces() {
+        let mut mutated = genome();
+        Swap.mutate(&mut mutated, 0.5, &mut StdRng::seed_from_u64(3));
+        assert_ne!(mutated, genome());
+
+        let sorted = |genome: &Genome| {
+            let mut forces = genome.force_matrix.clone();
+            forces.sort_by(f32::total_cmp);
+            forces
+        };
+        assert_eq!(sorted(&mutated), sorted(&genome()));
+    }
+
+    #[test]
+    fn mutated_genomes_stay_within_bounds() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut mutated = genome();
+        Reset.mutate(&mut mutated, 1.0, &mut rng);
+        assert!(mutated.force_matrix.iter().all(|force| force.abs() <= MAX_FORCE_WEIGHT));
+
+        // Un bruit large sort des bornes, que `Genome::clamp` rétablit
+        Gaussian { sigma: 4.0 }.mutate(&mut mutated, 1.0, &mut rng);
+        mutated.type_properties.fill(TypeProperties {
+            mass: *TypeProperties::MASS_RANGE.end(),
+            radius: *TypeProperties::RADIUS_RANGE.end(),
+            max_speed: *TypeProperties::MAX_SPEED_RANGE.end(),
+            damping: *TypeProperties::DAMPING_RANGE.end(),
+        });
+        mutate_type_properties(&mut mutated, 1.0, &mut rng);
+        assert!(mutated.force_matrix.iter().any(|force| force.abs() > MAX_FORCE_WEIGHT));
+        assert!(mutated.type_properties.iter().any(|&properties| properties != properties.clamped()));
+
+        mutated.clamp();
+        assert!(mutated.force_matrix.iter().all(|force| force.abs() <= MAX_FORCE_WEIGHT));
+        assert!(mutated.type_properties.iter().all(|&properties| properties == properties.clamped()));
+    }
+}
@@ -0,0 +1,168 @@
+use rand::{Rng, RngCore};
+
+use crate::resources::simulation_config::{SelectionMethod, SimulationConfig};
+
+/// Choix d'un parent d'après les scores de la génération évaluée.
+///
+/// `ranking` contient les couples (simulation, score) triés du meilleur au moins bon score ;
+/// il n'est jamais vide. Aucune physique n'est nécessaire pour tester une stratégie.
+pub trait SelectionStrategy: Send + Sync {
+    fn select(&self, ranking: &[(u32, f32)], rng: &mut dyn RngCore) -> u32;
+}
+
+/// Stratégie décrite par la configuration
+pub fn selection_strategy(config: &SimulationConfig) -> Box<dyn SelectionStrategy> {
+    match config.selection {
+        SelectionMethod::Tournament => Box::new(Tournament { size: config.tournament_size }),
+        SelectionMethod::Roulette => Box::new(Roulette),
+        SelectionMethod::Rank => Box::new(Rank),
+        SelectionMethod::Truncation => Box::new(Truncation { ratio: config.truncation_ratio }),
+    }
+}
+
+/// Meilleure de `size` simulations tirées au hasard (avec remise)
+pub struct Tournament {
+    pub size: usize,
+}
+
+impl SelectionStrategy for Tournament {
+    fn select(&self, ranking: &[(u32, f32)], rng: &mut dyn RngCore) -> u32 {
+        // Le classement étant trié, la meilleure est celle de plus petit rang
+        let best_rank = (0..self.size.max(1))
+            .map(|_| rng.random_range(0..ranking.len()))
+            .min()
+            .unwrap_or(0);
+        ranking[best_rank].0
+    }
+}
+
+/// Probabilité proportionnelle au score, décalé pour que le plus faible compte pour zéro
+pub struct Roulette;
+
+impl SelectionStrategy for Roulette {
+    fn select(&self, ranking: &[(u32, f32)], rng: &mut dyn RngCore) -> u32 {
+        let lowest = ranking.iter().map(|&(_, score)| score).fold(f32::INFINITY, f32::min);
+        let weights: Vec<f32> = ranking.iter().map(|&(_, score)| score - lowest).collect();
+        weighted_choice(ranking, &weights, rng)
+    }
+}
+
+/// Probabilité proportionnelle au rang : la meilleure de `n` pèse `n`, la dernière 1
+pub struct Rank;
+
+impl SelectionStrategy for Rank {
+    fn select(&self, ranking: &[(u32, f32)], rng: &mut dyn RngCore) -> u32 {
+        let weights: Vec<f32> = (0..ranking.len()).map(|rank| (ranking.len() - rank) as f32).collect();
+        weighted_choice(ranking, &weights, rng)
+    }
+}
+
+/// Tirage uniforme parmi la fraction `ratio` des meilleures
+pub struct Truncation {
+    pub ratio: f32,
+}
+
+impl SelectionStrategy for Truncation {
+    fn select(&self, ranking: &[(u32, f32)], rng: &mut dyn RngCore) -> u32 {
+        let kept = ((ranking.len() as f32 * self.ratio).ceil() as usize).clamp(1, ranking.len());
+        ranking[rng.random_range(0..kept)].0
+    }
+}
+
+/// Tirage pondéré ; uniforme si tous les poids sont nuls
+fn weighted_choice(ranking: &[(u32, f32)], weights: &[f32], rng: &mut dyn RngCore) -> u32 {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 || !total.is_finite() {
+        return ranking[rng.random_range(0..ranking.len())].0;
+    }
+
+    let mut target = rng.random::<f32>() * total;
+    for (&(sim, _), &weight) in ranking.iter().zip(weights) {
+        if target < weight {
+            return sim;
+        }
+        target -= weight;
+    }
+    // Arrondis : la cible peut dépasser de peu la dernière borne
+    ranking[ranking.len() - 1].0
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    const DRAWS: usize = 20_000;
+    const TOLERANCE: f32 = 0.02;
+
+    /// Classement trié du meilleur au moins bon, simulations numérotées dans le désordre
+    const RANKING: [(u32, f32); 4] = [(2, 10.0), (0, 6.0), (3, 3.0), (1, 1.0)];
+
+    /// Fréquence de sélection de chaque simulation, indexée par numéro de simulation
+    fn frequencies(strategy: &dyn SelectionStrategy, ranking: &[(u32, f32)]) -> Vec<f32> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = vec![0usize; ranking.len()];
+        for _ in 0..DRAWS {
+            counts[strategy.select(ranking, &mut rng) as usize] += 1;
+        }
+        counts.into_iter().map(|count| count as f32 / DRAWS as f32).collect()
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        for (sim, (a, e)) in actual.iter().zip(expected).enumerate() {
+            assert!((a - e).abs() < TOLERANCE, "simulation {} : fréquence {} au lieu de {}", sim, a, e);
+        }
+    }
+
+    #[test]
+    fn tournament_favours_better_ranks() {
+        // Meilleure de deux tirages : rang r choisi avec une probabilité ((n - r)² - (n - r - 1)²) / n²
+        let actual = frequencies(&Tournament { size: 2 }, &RANKING);
+        assert_close(&actual, &[5.0 / 16.0, 1.0 / 16.0, 7.0 / 16.0, 3.0 / 16.0]);
+
+        let actual = frequencies(&Tournament { size: 1 }, &RANKING);
+        assert_close(&actual, &[0.25; 4]);
+    }
+
+    #[test]
+    fn roulette_is_proportional_to_shifted_scores() {
+        // Poids 9, 5, 2 et 0 une fois le plus faible score ramené à zéro
+        let actual = frequencies(&Roulette, &RANKING);
+        assert_eq!(actual[1], 0.0);
+        assert_close(&actual, &[5.0 / 16.0, 0.0, 9.0 / 16.0, 2.0 / 16.0]);
+    }
+
+    #[test]
+    fn roulette_with_all_zero_scores_is_uniform() {
+        let actual = frequencies(&Roulette, &[(0, 0.0), (1, 0.0), (2, 0.0), (3, 0.0)]);
+        assert_close(&actual, &[0.25; 4]);
+    }
+
+    #[test]
+    fn roulette_handles_negative_scores() {
+        // Poids 4, 2 et 0
+        let actual = frequencies(&Roulette, &[(1, -1.0), (0, -3.0), (2, -5.0)]);
+        assert_eq!(actual[2], 0.0);
+        assert_close(&actual, &[1.0 / 3.0, 2.0 / 3.0, 0.0]);
+    }
+
+    #[test]
+    fn rank_is_proportional_to_rank() {
+        // Poids 4, 3, 2 et 1
+        let actual = frequencies(&Rank, &RANKING);
+        assert_close(&actual, &[0.3, 0.1, 0.4, 0.2]);
+    }
+
+    #[test]
+    fn truncation_keeps_only_the_best() {
+        let actual = frequencies(&Truncation { ratio: 0.5 }, &RANKING);
+        assert_eq!((actual[1], actual[3]), (0.0, 0.0));
+        assert_close(&actual, &[0.5, 0.0, 0.5, 0.0]);
+
+        // Au moins une simulation est toujours conservée
+        let actual = frequencies(&Truncation { ratio: 0.0 }, &RANKING);
+        assert_eq!(actual, vec![0.0, 0.0, 1.0, 0.0]);
+    }
+}
@@ -1,13 +1,24 @@
 pub const DEFAULT_PARTICLE_COUNT: usize = 1600;
 pub const DEFAULT_PARTICLE_TYPES: usize = 6;
 pub const DEFAULT_SIMULATION_COUNT: usize = 8;
+pub const MAX_PARTICLE_COUNT: usize = 200_000; // Backend CPU ; sur GPU, la limite vient du device
+pub const SPAWN_CHUNK_SIZE: usize = 262_144; // Particules créées par frame au lancement d'une simulation
+pub const MAX_SIMULATIONS: usize = 16;
 pub const DEFAULT_EPOCH_DURATION: f32 = 60.0; // secondes
 
 /// Timestep fixe pour la physique (60 FPS)
 pub const PHYSICS_TIMESTEP: f32 = 1.0 / 60.0;
+pub const MAX_STEPS_PER_FRAME: u32 = 64; // Sous-pas encodés au plus dans un lot
+pub const WORKGROUP_SIZE: u32 = 64; // Threads par groupe des passes par particule, identique aux shaders
+pub const DEFAULT_TIME_SCALE: f32 = 1.0;
+pub const MIN_TIME_SCALE: f32 = 0.1;
+pub const MAX_TIME_SCALE: f32 = 16.0;
+pub const DEFAULT_SUBSTEPS: u32 = 1;
+pub const MAX_SUBSTEPS: u32 = 8;
 
 // Paramètres de la grille
 pub const DEFAULT_WORLD_SIZE: f32 = 50.0;
+pub const MAX_GRID_DIM: u32 = 32; // Cellules par axe de la grille spatiale, par simulation
 
 // Paramètres de la nourriture
 pub const DEFAULT_FOOD_COUNT: usize = 200;
@@ -18,19 +29,32 @@ pub const FOOD_RADIUS: f32 = 1.0;
 // Paramètres des particules
 pub const PARTICLE_RADIUS: f32 = 2.5;
 pub const DEFAULT_PARTICLE_SIZE: f32 = 0.3;
-pub const MAX_VELOCITY: f32 = 200.0;
+pub const DEFAULT_MAX_VELOCITY: f32 = 200.0;
 pub const COLLISION_DAMPING: f32 = 0.5;
 
-// Paramètres des forces (équilibrés comme gpu-particle-life)
-pub const DEFAULT_MAX_FORCE_RANGE: f32 = 100.0;
-pub const FORCE_SCALE_FACTOR: f32 = 80.0;
-pub const MIN_DISTANCE: f32 = 0.001;
+// Paramètres des forces (équilibrés comme gpu-particle-life), valeurs par défaut des uniforms du shader
+pub const DEFAULT_MAX_FORCE_RANGE: f32 = 10.0;
+pub const DEFAULT_FORCE_SCALE: f32 = 10.0;
+pub const DEFAULT_MIN_DISTANCE: f32 = 0.5;
 pub const VELOCITY_HALF_LIFE: f32 = 0.043;
+pub const DEFAULT_FORCE_BETA: f32 = 0.3; // Zone de répulsion universelle (fraction de la portée)
+
+// Paramètres des bords du monde
+pub const DEFAULT_RESTITUTION: f32 = 0.8; // Vitesse conservée lors d'un rebond
+pub const DEFAULT_WALL_STIFFNESS: f32 = 100.0; // Raideur du mur mou
+pub const WALL_THICKNESS_RATIO: f32 = 0.1; // Épaisseur du mur mou (fraction du monde), identique au shader
+pub const DEFAULT_CENTERING_STRENGTH: f32 = 1.0; // Rappel vers le centre en monde ouvert
 
 // Paramètres génétiques
 pub const DEFAULT_ELITE_RATIO: f32 = 0.1; // 10% des génomes gardés
 pub const DEFAULT_MUTATION_RATE: f32 = 0.1; // 10% de chance de mutation
 pub const DEFAULT_CROSSOVER_RATE: f32 = 0.7; // 70% de crossover
+pub const DEFAULT_TOURNAMENT_SIZE: usize = 2; // Simulations comparées par tournoi
+pub const DEFAULT_TRUNCATION_RATIO: f32 = 0.5; // Moitié supérieure du classement
+pub const DEFAULT_BLEND_ALPHA: f32 = 0.5; // Élargissement de l'intervalle des parents (BLX-α)
+pub const DEFAULT_MUTATION_SIGMA: f32 = 0.3; // Écart type du bruit gaussien ajouté à une force mutée
+pub const CLUSTER_MIN_PARTICLES: usize = 8; // Particules d'une cellule dense, pour le compte des amas
+pub const MAX_FORCE_WEIGHT: f32 = 2.0; // Bornes des forces de la matrice, comme dans l'interface
 
 // Paramètres de rendu
 pub const PARTICLE_SUBDIVISIONS: u32 = 8;
\ No newline at end of file
@@ -1,26 +1,43 @@
 use bevy::prelude::*;
-use bevy_app_compute::prelude::*;
 use bevy_egui::{EguiContextPass, EguiPlugin};
 
 mod components;
+mod genetics;
 mod globals;
+mod physics;
 mod plugins;
 mod resources;
 mod states;
 mod systems;
 mod ui;
 
+use crate::plugins::bench_plugin::run_bench;
+use crate::plugins::headless_plugin::run_headless;
+use crate::plugins::parity_plugin::run_parity;
 use crate::plugins::particle_life_plugin::ParticleLifePlugin;
 use crate::states::app_state::AppState;
 use crate::ui::main_menu::{MenuConfig, main_menu_ui};
-use crate::ui::simulation_ui::{SimulationUI, force_matrix_window, simulations_list_ui};
+use crate::ui::simulation_ui::{SimulationUI, force_matrix_window, physics_window, reset_simulation_ui, simulations_list_ui};
+
+fn main() -> AppExit {
+    // `--headless` : session sans fenêtre pilotée par la ligne de commande
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--headless") {
+        return run_headless(&args);
+    }
+    // `--parity` : compare le compute shader à l'implémentation CPU
+    if args.iter().any(|arg| arg == "--parity") {
+        return run_parity(&args);
+    }
+    // `--bench` : mesure les performances sur une grille de configurations
+    if args.iter().any(|arg| arg == "--bench") {
+        return run_bench(&args);
+    }
 
-fn main() {
     println!("Starting Particle Life 3D - Enhanced Menu...");
 
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(AppComputePlugin)
         .add_plugins(EguiPlugin { enable_multipass_for_primary_context: true })
         .init_state::<AppState>()
         .init_resource::<MenuConfig>()
@@ -35,12 +52,14 @@ fn main() {
             ((
                 simulations_list_ui,
                 force_matrix_window,
+                physics_window,
                 handle_simulation_input,
             )
                 .run_if(in_state(AppState::Simulation)),),
         )
+        .add_systems(OnExit(AppState::Simulation), reset_simulation_ui)
         .add_systems(Update, check_state)
-        .run();
+        .run()
 }
 
 fn handle_simulation_input(
@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use crate::resources::particle_snapshot::ParticleSnapshot;
+use crate::resources::physics_params::TypeProperties;
+use crate::resources::simulation_config::SimulationConfig;
+
+/// Moteur physique pilotant les particules de toutes les simulations.
+///
+/// Les buffers suivent le format GPU : positions (x, y, z, type), vélocités (x, y, z, simulation),
+/// matrices de forces et propriétés des types concaténées, plages (offset, nombre) par simulation.
+pub trait SimulationBackend: Resource {
+    fn upload_particles(&mut self, positions: &[[f32; 4]], velocities: &[[f32; 4]], sim_ranges: &[[u32; 2]]);
+
+    fn set_force_matrices(&mut self, force_matrix: &[f32]);
+
+    fn set_type_properties(&mut self, type_properties: &[TypeProperties]);
+
+    /// Paramètres physiques, de grille et de pas de temps
+    fn set_physics(&mut self, config: &SimulationConfig);
+
+    /// Avance de `steps` pas, chacun découpé en `substeps` sous-pas
+    fn step(&mut self, steps: u32, substeps: u32);
+
+    /// État du plus ancien lot non relu, ou `None` s'il n'est pas encore disponible
+    fn poll_snapshot(&mut self) -> Option<ParticleSnapshot>;
+
+    /// Lots pouvant être demandés avant que le premier soit relu
+    fn max_batches_in_flight(&self) -> u32 {
+        1
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{BindGroup, BindGroupLayout, Buffer, ComputePipeline};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bytemuck::{NoUninit, cast_slice};
+use wgpu::util::BufferInitDescriptor;
+use wgpu::{
+    BindGroupEntry, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor,
+    ComputePipelineDescriptor, MapMode, ShaderModuleDescriptor, ShaderSource,
+};
+
+use crate::globals::WORKGROUP_SIZE;
+use crate::physics::backend::SimulationBackend;
+use crate::resources::particle_config::ParticleConfig;
+use crate::resources::particle_snapshot::ParticleSnapshot;
+use crate::resources::physics_params::{GridParams, PhysicsParams, TypeProperties};
+use crate::resources::simulation_config::SimulationConfig;
+
+/// Buffers de relecture utilisés à tour de rôle : le lot suivant est calculé pendant que
+/// l'état du précédent est mappé
+const READBACK_SLOTS: usize = 2;
+
+/// Compute shaders pilotés directement sur le device de Bevy.
+///
+/// Chaque lot n'encode que les passes des pas demandés : l'état alterne entre deux paires de
+/// buffers, `current` désignant celle qui contient le dernier pas.
+#[derive(Resource)]
+pub struct GpuBackend {
+    device: RenderDevice,
+    queue: RenderQueue,
+    num_particles: u32,
+    positions: [Buffer; 2],
+    velocities: [Buffer; 2],
+    current: usize,
+    /// État d'avant le lot, lu par le rendu pour interpoler jusqu'au lot suivant
+    previous_positions: Buffer,
+    dt: Buffer,
+    params: Buffer,
+    grid_params: Buffer,
+    force_matrix: Buffer,
+    sim_ranges: Buffer,
+    type_properties: Buffer,
+    /// Sans grille (recherche exhaustive), les passes de binning ne sont pas encodées
+    grid_enabled: bool,
+    pipelines: GpuPipelines,
+    bind_groups: GpuBindGroups,
+    particle_workgroups: [u32; 3],
+    cell_workgroups: [u32; 3],
+    readbacks: [Readback; READBACK_SLOTS],
+    /// Créneaux de relecture des lots soumis, du plus ancien au plus récent
+    pending_readbacks: VecDeque<usize>,
+}
+
+struct GpuPipelines {
+    previous_positions: ComputePipeline,
+    grid_clear: ComputePipeline,
+    grid_count: ComputePipeline,
+    grid_prefix_sum: ComputePipeline,
+    grid_scatter: ComputePipeline,
+    particle_compute: ComputePipeline,
+}
+
+/// Bind groups figés à la construction ; l'indice `i` lit la paire d'état `i` et écrit dans l'autre
+struct GpuBindGroups {
+    previous_positions: [BindGroup; 2],
+    grid_clear: BindGroup,
+    grid_count: [BindGroup; 2],
+    grid_prefix_sum: BindGroup,
+    grid_scatter: BindGroup,
+    particle_compute: [BindGroup; 2],
+}
+
+/// Copie de l'état en fin de lot, relue par le CPU une fois mappée
+struct Readback {
+    /// Positions puis vélocités
+    buffer: Buffer,
+    mapped: Arc<AtomicBool>,
+}
+
+impl GpuBackend {
+    pub fn new(
+        device: &RenderDevice,
+        queue: &RenderQueue,
+        particle_config: &ParticleConfig,
+        sim_config: &SimulationConfig,
+    ) -> Self {
+        let num_particles = particle_config.num_particles.max(1);
+        let grid_params = GridParams::from(sim_config);
+
+        // Les buffers de la grille sont dimensionnés pour la plus fine grille possible :
+        // la portée reste modifiable en cours de simulation sans reconstruire le backend
+        let max_cells = GridParams::max_cells(particle_config.num_simulations);
+        let max_per_dimension = device.limits().max_compute_workgroups_per_dimension;
+
+        println!("Initializing {} particles with {} types", num_particles, particle_config.num_types);
+
+        let state_size = num_particles as u64 * std::mem::size_of::<[f32; 4]>() as u64;
+        let state_usage = BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC;
+        let positions = [
+            empty_buffer(device, "positions_a", state_size, state_usage),
+            empty_buffer(device, "positions_b", state_size, state_usage),
+        ];
+        let velocities = [
+            empty_buffer(device, "velocities_a", state_size, state_usage),
+            empty_buffer(device, "velocities_b", state_size, state_usage),
+        ];
+        let previous_positions = empty_buffer(device, "previous_positions", state_size, state_usage);
+
+        let uniform = BufferUsages::UNIFORM | BufferUsages::COPY_DST;
+        let storage = BufferUsages::STORAGE | BufferUsages::COPY_DST;
+        let num_particles_buffer = data_buffer(device, "num_particles", &[num_particles], uniform);
+        let dt = data_buffer(device, "dt", &[sim_config.step_dt()], uniform);
+        let world_size = data_buffer(device, "world_size", &[particle_config.world_size], uniform);
+        let num_types = data_buffer(device, "num_types", &[particle_config.num_types], uniform);
+        let params = data_buffer(device, "params", &[PhysicsParams::from(sim_config)], uniform);
+        let grid_params_buffer = data_buffer(device, "grid_params", &[grid_params], uniform);
+        let force_matrix = data_buffer(device, "force_matrix", &particle_config.flattened_force_matrices(), storage);
+        let sim_ranges = data_buffer(device, "sim_ranges", &particle_config.padded_simulation_ranges(), storage);
+        let type_properties = data_buffer(device, "type_properties", &particle_config.flattened_type_properties(), storage);
+
+        let cell_counts = empty_buffer(device, "cell_counts", max_cells as u64 * 4, BufferUsages::STORAGE);
+        let cell_starts = empty_buffer(device, "cell_starts", (max_cells as u64 + 1) * 4, BufferUsages::STORAGE);
+        let particle_cells = empty_buffer(device, "particle_cells", num_particles as u64 * 8, BufferUsages::STORAGE);
+        let sorted_indices = empty_buffer(device, "sorted_indices", num_particles as u64 * 4, BufferUsages::STORAGE);
+
+        let pipelines = GpuPipelines {
+            previous_positions: compute_pipeline(device, "previous_positions", include_str!("../../assets/shaders/previous_positions.wgsl")),
+            grid_clear: compute_pipeline(device, "grid_clear", include_str!("../../assets/shaders/grid_clear.wgsl")),
+            grid_count: compute_pipeline(device, "grid_count", include_str!("../../assets/shaders/grid_count.wgsl")),
+            grid_prefix_sum: compute_pipeline(device, "grid_prefix_sum", include_str!("../../assets/shaders/grid_prefix_sum.wgsl")),
+            grid_scatter: compute_pipeline(device, "grid_scatter", include_str!("../../assets/shaders/grid_scatter.wgsl")),
+            particle_compute: compute_pipeline(device, "particle_compute", include_str!("../../assets/shaders/particle_compute.wgsl")),
+        };
+
+        // Liaisons dans l'ordre des `@binding` de chaque shader
+        let both = |build: &dyn Fn(usize) -> BindGroup| [build(0), build(1)];
+        let bind_groups = GpuBindGroups {
+            previous_positions: both(&|i| bind_group(device, &pipelines.previous_positions, &[
+                &num_particles_buffer, &positions[i], &previous_positions,
+            ])),
+            grid_clear: bind_group(device, &pipelines.grid_clear, &[&grid_params_buffer, &cell_counts]),
+            grid_count: both(&|i| bind_group(device, &pipelines.grid_count, &[
+                &num_particles_buffer, &positions[i], &velocities[i], &grid_params_buffer, &cell_counts, &particle_cells,
+            ])),
+            grid_prefix_sum: bind_group(device, &pipelines.grid_prefix_sum, &[&grid_params_buffer, &cell_counts, &cell_starts]),
+            grid_scatter: bind_group(device, &pipelines.grid_scatter, &[
+                &num_particles_buffer, &grid_params_buffer, &particle_cells, &cell_starts, &sorted_indices,
+            ]),
+            particle_compute: both(&|i| bind_group(device, &pipelines.particle_compute, &[
+                &num_particles_buffer, &dt, &world_size, &num_types,
+                &positions[i], &velocities[i], &positions[1 - i], &velocities[1 - i],
+                &force_matrix, &sim_ranges, &params, &grid_params_buffer, &cell_starts, &sorted_indices,
+                &type_properties,
+            ])),
+        };
+
+        let readbacks = [0, 1].map(|_| Readback {
+            buffer: empty_buffer(device, "readback", state_size * 2, BufferUsages::MAP_READ | BufferUsages::COPY_DST),
+            mapped: Arc::new(AtomicBool::new(false)),
+        });
+
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            num_particles,
+            positions,
+            velocities,
+            current: 0,
+            previous_positions,
+            dt,
+            params,
+            grid_params: grid_params_buffer,
+            force_matrix,
+            sim_ranges,
+            type_properties,
+            grid_enabled: grid_params.enabled != 0,
+            pipelines,
+            bind_groups,
+            particle_workgroups: dispatch_size(num_particles, max_per_dimension),
+            cell_workgroups: dispatch_size(max_cells, max_per_dimension),
+            readbacks,
+            pending_readbacks: VecDeque::with_capacity(READBACK_SLOTS),
+        }
+    }
+
+    /// Buffer contenant le dernier état calculé, lu directement par le rendu
+    pub fn positions_buffer(&self) -> &Buffer {
+        &self.positions[self.current]
+    }
+
+    pub fn previous_positions_buffer(&self) -> &Buffer {
+        &self.previous_positions
+    }
+
+    /// Bloque jusqu'à la fin des lots soumis, pour chronométrer le calcul GPU lui-même
+    pub fn wait_for_submissions(&self) {
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+
+    fn write<T: NoUninit>(&self, buffer: &Buffer, data: &[T]) {
+        self.queue.write_buffer(buffer, 0, cast_slice(data));
+    }
+}
+
+impl SimulationBackend for GpuBackend {
+    fn upload_particles(&mut self, positions: &[[f32; 4]], velocities: &[[f32; 4]], sim_ranges: &[[u32; 2]]) {
+        self.write(&self.positions[self.current], positions);
+        // Rien à interpoler avant le premier lot de la nouvelle population
+        self.write(&self.previous_positions, positions);
+        self.write(&self.velocities[self.current], velocities);
+        self.write(&self.sim_ranges, sim_ranges);
+    }
+
+    fn set_force_matrices(&mut self, force_matrix: &[f32]) {
+        self.write(&self.force_matrix, force_matrix);
+    }
+
+    fn set_type_properties(&mut self, type_properties: &[TypeProperties]) {
+        self.write(&self.type_properties, type_properties);
+    }
+
+    fn set_physics(&mut self, config: &SimulationConfig) {
+        let grid_params = GridParams::from(config);
+        self.write(&self.dt, &[config.step_dt()]);
+        self.write(&self.params, &[PhysicsParams::from(config)]);
+        self.write(&self.grid_params, &[grid_params]);
+        self.grid_enabled = grid_params.enabled != 0;
+    }
+
+    fn step(&mut self, steps: u32, substeps: u32) {
+        // Un créneau de relecture doit être libre : sa copie précédente a été relue
+        let Some(slot) = (0..READBACK_SLOTS).find(|slot| !self.pending_readbacks.contains(slot)) else {
+            return;
+        };
+
+        let mut encoder = self.device.create_command_encoder(&CommandEncoderDescriptor { label: Some("particle_step") });
+        {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor { label: Some("particle_step"), timestamp_writes: None });
+            let dispatch = |pass: &mut wgpu::ComputePass, pipeline: &ComputePipeline, bind_group: &BindGroup, workgroups: [u32; 3]| {
+                pass.set_pipeline(pipeline);
+                pass.set_bind_group(0, &**bind_group, &[]);
+                pass.dispatch_workgroups(workgroups[0], workgroups[1], workgroups[2]);
+            };
+
+            dispatch(&mut pass, &self.pipelines.previous_positions, &self.bind_groups.previous_positions[self.current], self.particle_workgroups);
+
+            // Chaque sous-pas lit la paire courante et écrit dans l'autre
+            for _ in 0..steps * substeps {
+                let i = self.current;
+                if self.grid_enabled {
+                    dispatch(&mut pass, &self.pipelines.grid_clear, &self.bind_groups.grid_clear, self.cell_workgroups);
+                    dispatch(&mut pass, &self.pipelines.grid_count, &self.bind_groups.grid_count[i], self.particle_workgroups);
+                    dispatch(&mut pass, &self.pipelines.grid_prefix_sum, &self.bind_groups.grid_prefix_sum, [1, 1, 1]);
+                    dispatch(&mut pass, &self.pipelines.grid_scatter, &self.bind_groups.grid_scatter, self.particle_workgroups);
+                }
+                dispatch(&mut pass, &self.pipelines.particle_compute, &self.bind_groups.particle_compute[i], self.particle_workgroups);
+                self.current = 1 - i;
+            }
+        }
+
+        let readback = &self.readbacks[slot];
+        let state_size = self.positions[0].size();
+        encoder.copy_buffer_to_buffer(&self.positions[self.current], 0, &readback.buffer, 0, state_size);
+        encoder.copy_buffer_to_buffer(&self.velocities[self.current], 0, &readback.buffer, state_size, state_size);
+        self.queue.submit([encoder.finish()]);
+
+        // Asynchrone : la frame n'attend pas le GPU, l'état est relu dès qu'il est prêt
+        let mapped = readback.mapped.clone();
+        readback.buffer.slice(..).map_async(MapMode::Read, move |result| {
+            if let Err(error) = result {
+                panic!("Relecture de l'état GPU impossible : {}", error);
+            }
+            mapped.store(true, Ordering::Release);
+        });
+        self.pending_readbacks.push_back(slot);
+    }
+
+    fn poll_snapshot(&mut self) -> Option<ParticleSnapshot> {
+        // Les lots se terminent dans l'ordre de soumission : seul le plus ancien peut être prêt
+        let &slot = self.pending_readbacks.front()?;
+        let readback = &self.readbacks[slot];
+
+        self.device.poll(wgpu::Maintain::Poll);
+        if !readback.mapped.swap(false, Ordering::Acquire) {
+            return None;
+        }
+
+        let num_particles = self.num_particles as usize;
+        let snapshot = {
+            let data = readback.buffer.slice(..).get_mapped_range();
+            let state: &[[f32; 4]] = cast_slice(&data);
+            ParticleSnapshot {
+                positions: state[..num_particles].to_vec(),
+                velocities: state[num_particles..].to_vec(),
+            }
+        };
+        readback.buffer.unmap();
+        self.pending_readbacks.pop_front();
+
+        Some(snapshot)
+    }
+
+    fn max_batches_in_flight(&self) -> u32 {
+        READBACK_SLOTS as u32
+    }
+}
+
+/// Groupes couvrant `count` éléments, répartis sur x puis y pour rester sous la limite de groupes
+/// par dimension ; les shaders recomposent l'index à partir de `num_workgroups`
+fn dispatch_size(count: u32, max_per_dimension: u32) -> [u32; 3] {
+    let workgroups = count.div_ceil(WORKGROUP_SIZE).max(1);
+    let x = workgroups.min(max_per_dimension.max(1));
+    [x, workgroups.div_ceil(x), 1]
+}
+
+fn empty_buffer(device: &RenderDevice, label: &'static str, size: u64, usage: BufferUsages) -> Buffer {
+    device.create_buffer(&BufferDescriptor {
+        label: Some(label),
+        size,
+        usage,
+        mapped_at_creation: false,
+    })
+}
+
+fn data_buffer<T: NoUninit>(device: &RenderDevice, label: &'static str, data: &[T], usage: BufferUsages) -> Buffer {
+    device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some(label),
+        contents: cast_slice(data),
+        usage,
+    })
+}
+
+fn compute_pipeline(device: &RenderDevice, label: &'static str, source: &'static str) -> ComputePipeline {
+    let module = device.create_and_validate_shader_module(ShaderModuleDescriptor {
+        label: Some(label),
+        source: ShaderSource::Wgsl(source.into()),
+    });
+
+    // Disposition déduite du shader
+    device.create_compute_pipeline(&ComputePipelineDescriptor {
+        label: Some(label),
+        layout: None,
+        module: &module,
+        entry_point: Some("main"),
+        compilation_options: default(),
+        cache: None,
+    })
+}
+
+fn bind_group(device: &RenderDevice, pipeline: &ComputePipeline, buffers: &[&Buffer]) -> BindGroup {
+    let layout = BindGroupLayout::from(pipeline.get_bind_group_layout(0));
+    let entries: Vec<BindGroupEntry> = buffers.iter().enumerate()
+        .map(|(binding, buffer)| BindGroupEntry {
+            binding: binding as u32,
+            resource: buffer.as_entire_binding(),
+        })
+        .collect();
+    device.create_bind_group(None, &layout, &entries)
+}
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+
+use crate::physics::kernel::ForceField;
+use crate::resources::physics_params::GridParams;
+
+/// Version CPU du binning des passes grid_*.wgsl (comptage, somme préfixe, tri)
+pub struct SpatialGrid {
+    params: GridParams,
+    /// Début de chaque cellule dans `sorted_indices` (`num_cells + 1` entrées)
+    cell_starts: Vec<u32>,
+    sorted_indices: Vec<u32>,
+}
+
+impl SpatialGrid {
+    /// `velocities[i][3]` porte l'index de simulation, comme dans les buffers GPU
+    pub fn build(params: GridParams, positions: &[[f32; 4]], velocities: &[[f32; 4]]) -> Self {
+        let num_cells = params.num_cells as usize;
+        let mut counts = vec![0u32; num_cells];
+        let mut particle_cells = Vec::with_capacity(positions.len());
+
+        for (position, velocity) in positions.iter().zip(velocities) {
+            let cell = cell_index(&params, velocity[3] as u32, cell_coords(&params, position)) as usize;
+            let slot = counts[cell];
+            counts[cell] += 1;
+            particle_cells.push((cell, slot));
+        }
+
+        let mut cell_starts = Vec::with_capacity(num_cells + 1);
+        let mut running = 0;
+        for count in &counts {
+            cell_starts.push(running);
+            running += count;
+        }
+        cell_starts.push(running);
+
+        let mut sorted_indices = vec![0u32; running as usize];
+        for (particle_index, (cell, slot)) in particle_cells.into_iter().enumerate() {
+            sorted_indices[(cell_starts[cell] + slot) as usize] = particle_index as u32;
+        }
+
+        Self { params, cell_starts, sorted_indices }
+    }
+
+    /// Indices des particules situées dans les 27 cellules autour de `position`,
+    /// repliées de l'autre côté du monde si `wrap`
+    pub fn neighbors(&self, sim_index: u32, position: &[f32; 4], wrap: bool) -> Vec<usize> {
+        let dim = self.params.grid_dim as i32;
+        let home = cell_coords(&self.params, position);
+        let (lower, upper) = if !wrap {
+            ((home - IVec3::ONE).max(IVec3::ZERO), (home + IVec3::ONE).min(IVec3::splat(dim - 1)))
+        } else if dim >= 3 {
+            (home - IVec3::ONE, home + IVec3::ONE)
+        } else {
+            // Moins de 3 cellules par axe : tout l'axe, sans visiter une cellule deux fois
+            (IVec3::ZERO, IVec3::splat(dim - 1))
+        };
+
+        let mut neighbors = Vec::new();
+        for z in lower.z..=upper.z {
+            for y in lower.y..=upper.y {
+                for x in lower.x..=upper.x {
+                    let coords = IVec3::new(x, y, z).rem_euclid(IVec3::splat(dim));
+                    let cell = cell_index(&self.params, sim_index, coords) as usize;
+                    let (start, end) = (self.cell_starts[cell] as usize, self.cell_starts[cell + 1] as usize);
+                    neighbors.extend(self.sorted_indices[start..end].iter().map(|&i| i as usize));
+                }
+            }
+        }
+        neighbors
+    }
+}
+
+fn cell_coords(params: &GridParams, position: &[f32; 4]) -> IVec3 {
+    let pos = Vec3::new(position[0], position[1], position[2]);
+    let coords = ((pos + Vec3::splat(params.half_world)) / params.cell_size).floor().as_ivec3();
+    coords.clamp(IVec3::ZERO, IVec3::splat(params.grid_dim as i32 - 1))
+}
+
+fn cell_index(params: &GridParams, sim_index: u32, coords: IVec3) -> u32 {
+    let dim = params.grid_dim;
+    sim_index * dim * dim * dim + (coords.z as u32 * dim + coords.y as u32) * dim + coords.x as u32
+}
+
+/// Résultat de la comparaison grille / force brute
+pub struct GridCheckReport {
+    pub particles: usize,
+    pub max_error: f32,
+    pub max_force: f32,
+}
+
+impl GridCheckReport {
+    /// Écart relatif à la plus grande force observée (les sommes ne sont pas faites dans le même ordre)
+    pub fn relative_error(&self) -> f32 {
+        self.max_error / self.max_force.max(1.0)
+    }
+
+    pub fn matches(&self) -> bool {
+        self.relative_error() < 1e-4
+    }
+}
+
+/// Compare pour chaque particule la force calculée via la grille à celle de la boucle complète
+pub fn compare_with_brute_force(
+    field: &ForceField,
+    grid_params: GridParams,
+    positions: &[[f32; 4]],
+    velocities: &[[f32; 4]],
+    sim_ranges: &[[u32; 2]],
+) -> GridCheckReport {
+    let grid = SpatialGrid::build(grid_params, positions, velocities);
+    let mut report = GridCheckReport { particles: 0, max_error: 0.0, max_force: 0.0 };
+
+    for (sim_index, &[offset, count]) in sim_ranges.iter().enumerate() {
+        let sim_index = sim_index as u32;
+        let range = offset as usize..(offset + count) as usize;
+
+        for particle_index in range.clone() {
+            let brute = field.total_force(positions, sim_index, particle_index, range.clone());
+            let binned = field.total_force(
+                positions,
+                sim_index,
+                particle_index,
+                grid.neighbors(sim_index, &positions[particle_index], field.wraps()),
+            );
+
+            report.particles += 1;
+            report.max_error = report.max_error.max((brute - binned).length());
+            report.max_force = report.max_force.max(brute.length());
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::physics_params::{PhysicsParams, TypeProperties};
+    use crate::resources::simulation_config::{BoundaryMode, SimulationConfig};
+
+    const NUM_TYPES: u32 = 2;
+
+    /// Monde de 40 unités, grille de 4×4×4 cellules de 10 unités, portée 10
+    fn config(boundary_mode: BoundaryMode) -> SimulationConfig {
+        SimulationConfig {
+            world_size: 40.0,
+            max_force_range: 10.0,
+            simulation_count: 2,
+            particle_types: NUM_TYPES as usize,
+            boundary_mode,
+            ..default()
+        }
+    }
+
+    /// Paires de part et d'autre des bords de cellule et des bords du monde, puis un remplissage régulier
+    fn population() -> Vec<[f32; 3]> {
+        let mut points = vec![
+            [-0.1, 0.0, 0.0], [0.1, 0.0, 0.0],
+            [9.9, 5.0, 5.0], [10.1, 5.0, 5.0],
+            [-10.05, -9.95, 3.0], [-9.95, -10.05, 3.0],
+            [-19.9, 0.0, 0.0], [19.9, 0.0, 0.0],
+            [0.0, -19.95, 12.0], [0.0, 19.95, 12.0],
+            [-19.9, -19.9, -19.9], [19.9, 19.9, 19.9],
+            [15.0, -19.99, 19.99], [15.0, 19.99, -19.99],
+        ];
+        for i in 0..50 {
+            let t = i as f32;
+            points.push([(t * 7.3) % 40.0 - 20.0, (t * 3.1) % 40.0 - 20.0, (t * 5.7) % 40.0 - 20.0]);
+        }
+        points
+    }
+
+    fn check(boundary_mode: BoundaryMode) -> GridCheckReport {
+        let config = config(boundary_mode);
+        let params = PhysicsParams::from(&config);
+        let force_matrix: Vec<f32> = (0..2 * NUM_TYPES * NUM_TYPES).map(|i| if i % 3 == 0 { -0.6 } else { 0.8 }).collect();
+        let type_properties = vec![TypeProperties::default(); (2 * NUM_TYPES) as usize];
+        let field = ForceField {
+            params: &params,
+            force_matrix: &force_matrix,
+            type_properties: &type_properties,
+            num_types: NUM_TYPES,
+            world_size: config.world_size,
+        };
+
+        // La même population dans les deux simulations, pour vérifier que leurs grilles restent séparées
+        let points = population();
+        let mut positions = Vec::new();
+        let mut velocities = Vec::new();
+        let mut sim_ranges = Vec::new();
+        for sim in 0..2 {
+            sim_ranges.push([positions.len() as u32, points.len() as u32]);
+            for (i, point) in points.iter().enumerate() {
+                positions.push([point[0], point[1], point[2], (i as u32 % NUM_TYPES) as f32]);
+                velocities.push([0.0, 0.0, 0.0, sim as f32]);
+            }
+        }
+
+        compare_with_brute_force(&field, GridParams::from(&config), &positions, &velocities, &sim_ranges)
+    }
+
+    #[test]
+    fn grid_matches_brute_force_with_walls() {
+        let report = check(BoundaryMode::Reflect);
+        assert_eq!(report.particles, 2 * population().len());
+        assert!(report.max_force > 0.0);
+        assert!(report.matches(), "écart relatif {}", report.relative_error());
+    }
+
+    #[test]
+    fn grid_matches_brute_force_across_wrapped_edges() {
+        let report = check(BoundaryMode::Wrap);
+        assert_eq!(report.particles, 2 * population().len());
+        assert!(report.max_force > 0.0);
+        assert!(report.matches(), "écart relatif {}", report.relative_error());
+    }
+}
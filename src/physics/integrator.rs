@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+use crate::physics::grid::SpatialGrid;
+use crate::physics::kernel::ForceField;
+
+/// Un pas d'intégration d'une particule, miroir de `main` dans particle_compute.wgsl.
+/// Renvoie la nouvelle position (w = type) et la nouvelle vitesse (w = simulation).
+pub fn integrate_particle(
+    field: &ForceField,
+    grid: Option<&SpatialGrid>,
+    positions: &[[f32; 4]],
+    velocities: &[[f32; 4]],
+    sim_ranges: &[[u32; 2]],
+    particle_index: usize,
+    dt: f32,
+) -> ([f32; 4], [f32; 4]) {
+    let [x, y, z, particle_type] = positions[particle_index];
+    let [vx, vy, vz, sim] = velocities[particle_index];
+    let sim_index = sim as u32;
+    let current_pos = Vec3::new(x, y, z);
+
+    let mut total_force = match grid {
+        Some(grid) => field.total_force(
+            positions,
+            sim_index,
+            particle_index,
+            grid.neighbors(sim_index, &positions[particle_index], field.wraps()),
+        ),
+        None => {
+            let [offset, count] = sim_ranges.get(sim_index as usize).copied().unwrap_or_default();
+            field.total_force(positions, sim_index, particle_index, offset as usize..(offset + count) as usize)
+        }
+    };
+    total_force += field.boundary_force(current_pos);
+    let plane = field.plane();
+    total_force *= plane;
+
+    let properties = field.type_properties(sim_index, particle_type as u32);
+    let mut vel = Vec3::new(vx, vy, vz);
+    if total_force.length() > 0.01 {
+        vel += total_force / properties.mass * dt;
+    }
+
+    vel *= field.params.friction.powf(properties.damping) * plane;
+    vel = vel.clamp_length_max(field.params.max_velocity * properties.max_speed);
+
+    let mut pos = current_pos + vel * dt;
+    field.apply_boundary(&mut pos, &mut vel);
+    pos *= plane;
+
+    ([pos.x, pos.y, pos.z, particle_type], [vel.x, vel.y, vel.z, sim])
+}
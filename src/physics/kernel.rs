@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+
+use crate::globals::WALL_THICKNESS_RATIO;
+
+use crate::resources::physics_params::{PhysicsParams, TypeProperties};
+use crate::resources::simulation_config::{BoundaryMode, Dimension, ForceModel};
+
+/// Garde-fou numérique du modèle en 1/r² (identique au shader)
+const MAX_FORCE: f32 = 1_000_000.0;
+
+/// Version CPU de la passe de forces de particle_compute.wgsl, servant de référence
+pub struct ForceField<'a> {
+    pub params: &'a PhysicsParams,
+    /// Matrices concaténées, au format du buffer `force_matrix`
+    pub force_matrix: &'a [f32],
+    /// Propriétés concaténées, au format du buffer `type_properties`
+    pub type_properties: &'a [TypeProperties],
+    pub num_types: u32,
+    pub world_size: f32,
+}
+
+impl ForceField<'_> {
+    fn force_between_types(&self, sim_index: u32, type_a: u32, type_b: u32) -> f32 {
+        let n = self.num_types;
+        let index = (sim_index * n * n + type_a * n + type_b) as usize;
+        self.force_matrix.get(index).copied().unwrap_or(0.0)
+    }
+
+    pub fn type_properties(&self, sim_index: u32, particle_type: u32) -> TypeProperties {
+        let index = (sim_index * self.num_types + particle_type) as usize;
+        self.type_properties.get(index).copied().unwrap_or_default()
+    }
+
+    /// Vrai si le monde est torique (distances en image minimale, grille repliée)
+    pub fn wraps(&self) -> bool {
+        self.params.boundary_mode == BoundaryMode::Wrap.shader_id()
+    }
+
+    pub fn minimum_image(&self, diff: Vec3) -> Vec3 {
+        if !self.wraps() {
+            return diff;
+        }
+        diff - self.world_size * (diff / self.world_size).round()
+    }
+
+    fn particle_life_kernel(&self, r: f32, attraction: f32) -> f32 {
+        let beta = self.params.beta;
+        if r < beta {
+            r / beta - 1.0
+        } else if r < 1.0 {
+            attraction * (1.0 - (2.0 * r - 1.0 - beta).abs() / (1.0 - beta))
+        } else {
+            0.0
+        }
+    }
+
+    /// Force exercée par une particule en `pos_b` sur une particule en `pos_a`
+    pub fn pair_force(&self, pos_a: Vec3, pos_b: Vec3, type_a: u32, type_b: u32, sim_index: u32) -> Vec3 {
+        let diff = self.minimum_image(pos_b - pos_a);
+        let distance = diff.length();
+        let strength = self.force_between_types(sim_index, type_a, type_b);
+        // Portée propre au type qui subit la force
+        let range = self.params.max_force_range * self.type_properties(sim_index, type_a).radius;
+
+        if self.params.force_model == ForceModel::ParticleLife.shader_id() {
+            if distance <= 0.0 || distance >= range {
+                return Vec3::ZERO;
+            }
+
+            let kernel = self.particle_life_kernel(distance / range, strength);
+            return (diff / distance) * kernel * range * self.params.force_scale;
+        }
+
+        if distance < self.params.min_distance || distance > range {
+            return Vec3::ZERO;
+        }
+
+        let magnitude = strength * self.params.force_scale / (distance * distance);
+        diff.normalize() * magnitude.clamp(-MAX_FORCE, MAX_FORCE)
+    }
+
+    /// Force totale subie par `particle_index`, en parcourant `candidates`
+    pub fn total_force(
+        &self,
+        positions: &[[f32; 4]],
+        sim_index: u32,
+        particle_index: usize,
+        candidates: impl IntoIterator<Item = usize>,
+    ) -> Vec3 {
+        let [x, y, z, particle_type] = positions[particle_index];
+        let current_pos = Vec3::new(x, y, z);
+
+        candidates
+            .into_iter()
+            .filter(|&i| i != particle_index)
+            .map(|i| {
+                let [ox, oy, oz, other_type] = positions[i];
+                self.pair_force(current_pos, Vec3::new(ox, oy, oz), particle_type as u32, other_type as u32, sim_index)
+            })
+            .sum()
+    }
+
+    /// Masque des axes simulés : z est neutralisé en 2D
+    pub fn plane(&self) -> Vec3 {
+        if self.params.dimensions == Dimension::Two.shader_id() {
+            Vec3::new(1.0, 1.0, 0.0)
+        } else {
+            Vec3::ONE
+        }
+    }
+
+    /// Force exercée par les bords : mur mou ou rappel vers le centre
+    pub fn boundary_force(&self, pos: Vec3) -> Vec3 {
+        let mode = self.params.boundary_mode;
+        if mode == BoundaryMode::SoftWall.shader_id() {
+            let inner = self.world_size * (0.5 - WALL_THICKNESS_RATIO);
+            let penetration = (pos.abs() - Vec3::splat(inner)).max(Vec3::ZERO);
+            return -pos.signum() * penetration * self.params.wall_stiffness;
+        }
+        if mode == BoundaryMode::Unbounded.shader_id() {
+            return -pos * self.params.centering_strength;
+        }
+        Vec3::ZERO
+    }
+
+    /// Conditions aux bords appliquées après l'intégration (repli torique ou murs rigides)
+    pub fn apply_boundary(&self, pos: &mut Vec3, vel: &mut Vec3) {
+        let half_size = self.world_size * 0.5;
+        let mode = self.params.boundary_mode;
+
+        if mode == BoundaryMode::Wrap.shader_id() {
+            *pos -= self.world_size * ((*pos + Vec3::splat(half_size)) / self.world_size).floor();
+        } else if mode == BoundaryMode::Reflect.shader_id() {
+            for axis in 0..3 {
+                if pos[axis] > half_size {
+                    pos[axis] = half_size;
+                    vel[axis] = -vel[axis].abs() * self.params.restitution;
+                }
+                if pos[axis] < -half_size {
+                    pos[axis] = -half_size;
+                    vel[axis] = vel[axis].abs() * self.params.restitution;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod backend;
+pub mod cpu_backend;
+pub mod gpu_backend;
+pub mod grid;
+pub mod integrator;
+pub mod kernel;
+pub mod parity;
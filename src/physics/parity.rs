@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::physics::kernel::ForceField;
+use crate::resources::particle_snapshot::ParticleSnapshot;
+
+/// Écart d'une particule entre les deux backends
+pub struct ParticleDivergence {
+    pub index: usize,
+    pub simulation: u32,
+    pub position_error: f32,
+    pub velocity_error: f32,
+}
+
+/// Résultat de la comparaison GPU / CPU après le même nombre de pas
+pub struct ParityReport {
+    pub particles: usize,
+    pub tolerance: f32,
+    pub max_position_error: f32,
+    pub max_velocity_error: f32,
+    /// Particules dont la position ou la vitesse s'écarte de plus de `tolerance`
+    pub divergent: Vec<ParticleDivergence>,
+    /// Particules présentes dans un seul des deux états
+    pub missing: usize,
+}
+
+impl ParityReport {
+    pub fn matches(&self) -> bool {
+        self.divergent.is_empty() && self.missing == 0
+    }
+}
+
+/// Compare particule par particule deux états issus des mêmes conditions initiales.
+/// En mode Wrap, une particule repliée d'un côté et pas de l'autre n'est pas un écart.
+pub fn compare_snapshots(
+    field: &ForceField,
+    reference: &ParticleSnapshot,
+    candidate: &ParticleSnapshot,
+    tolerance: f32,
+) -> ParityReport {
+    let mut report = ParityReport {
+        particles: 0,
+        tolerance,
+        max_position_error: 0.0,
+        max_velocity_error: 0.0,
+        divergent: Vec::new(),
+        missing: 0,
+    };
+
+    // Une valeur NaN n'est jamais dans la tolérance
+    let within = |error: f32| error <= tolerance;
+    let particles = reference.positions.iter().zip(&reference.velocities)
+        .zip(candidate.positions.iter().zip(&candidate.velocities));
+
+    for (index, ((ref_pos, ref_vel), (pos, vel))) in particles.enumerate() {
+        let position_error = field.minimum_image(Vec3::from_slice(pos) - Vec3::from_slice(ref_pos)).length();
+        let velocity_error = (Vec3::from_slice(vel) - Vec3::from_slice(ref_vel)).length();
+
+        report.particles += 1;
+        report.max_position_error = report.max_position_error.max(position_error);
+        report.max_velocity_error = report.max_velocity_error.max(velocity_error);
+
+        if !(within(position_error) && within(velocity_error)) {
+            report.divergent.push(ParticleDivergence {
+                index,
+                simulation: ref_vel[3] as u32,
+                position_error,
+                velocity_error,
+            });
+        }
+    }
+
+    let len = |snapshot: &ParticleSnapshot| snapshot.positions.len().max(snapshot.velocities.len());
+    report.missing = len(reference).max(len(candidate)) - report.particles;
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::physics_params::PhysicsParams;
+    use crate::resources::simulation_config::SimulationConfig;
+
+    const TOLERANCE: f32 = 1e-3;
+
+    fn compare(reference: &ParticleSnapshot, candidate: &ParticleSnapshot) -> ParityReport {
+        let params = PhysicsParams::from(&SimulationConfig::default());
+        let field = ForceField {
+            params: &params,
+            force_matrix: &[],
+            type_properties: &[],
+            num_types: 1,
+            world_size: 50.0,
+        };
+        compare_snapshots(&field, reference, candidate, TOLERANCE)
+    }
+
+    fn snapshot() -> ParticleSnapshot {
+        ParticleSnapshot {
+            positions: vec![[1.0, 2.0, 3.0, 0.0], [-4.0, 5.0, -6.0, 1.0], [7.0, -8.0, 9.0, 0.0]],
+            velocities: vec![[0.1, 0.2, 0.3, 0.0], [0.0, -1.0, 0.5, 0.0], [2.0, 0.0, -2.0, 1.0]],
+        }
+    }
+
+    #[test]
+    fn identical_snapshots_match() {
+        let report = compare(&snapshot(), &snapshot());
+        assert!(report.matches());
+        assert_eq!(report.particles, 3);
+        assert_eq!(report.max_position_error, 0.0);
+        assert_eq!(report.max_velocity_error, 0.0);
+    }
+
+    #[test]
+    fn small_errors_within_tolerance_match() {
+        let mut candidate = snapshot();
+        candidate.positions[1][0] += TOLERANCE * 0.5;
+        candidate.velocities[2][1] -= TOLERANCE * 0.5;
+
+        let report = compare(&snapshot(), &candidate);
+        assert!(report.matches());
+        assert!(report.max_position_error > 0.0 && report.max_position_error <= TOLERANCE);
+        assert!(report.max_velocity_error > 0.0 && report.max_velocity_error <= TOLERANCE);
+    }
+
+    #[test]
+    fn errors_beyond_tolerance_are_reported() {
+        let mut candidate = snapshot();
+        candidate.positions[0][2] += 0.5;
+        candidate.velocities[2][0] = f32::NAN;
+
+        let report = compare(&snapshot(), &candidate);
+        assert!(!report.matches());
+        let divergent: Vec<(usize, u32)> = report.divergent.iter().map(|d| (d.index, d.simulation)).collect();
+        assert_eq!(divergent, vec![(0, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn different_lengths_do_not_match() {
+        let mut candidate = snapshot();
+        candidate.positions.pop();
+        candidate.velocities.pop();
+
+        let report = compare(&snapshot(), &candidate);
+        assert!(report.divergent.is_empty());
+        assert_eq!(report.particles, 2);
+        assert_eq!(report.missing, 1);
+        assert!(!report.matches());
+
+        let report = compare(&candidate, &snapshot());
+        assert_eq!(report.missing, 1);
+        assert!(!report.matches());
+    }
+}
@@ -0,0 +1,456 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+
+use crate::globals::*;
+use crate::physics::{cpu_backend::CpuBackend, gpu_backend::GpuBackend};
+use crate::plugins::headless_plugin::{base_config, parse};
+use crate::plugins::particle_life_plugin::{GpuBackendPlugin, SimulationCorePlugin, gpu_adapter_available};
+use crate::resources::{particle_snapshot::*, simulation_config::*};
+use crate::states::app_state::AppState;
+use crate::systems::food_system::handle_food_interactions;
+use crate::systems::simulation_system::*;
+
+const USAGE: &str = "\
+Usage: truc --bench [options]
+  --config <fichier.ron>    SimulationConfig de base (bords, modèle de force, nourriture…)
+  --particles <n,n,…>       Nombres de particules, toutes simulations confondues (défaut : 1000,4000,16000)
+  --types <n,n,…>           Nombres de types (défaut : 3,6)
+  --simulations <n,n,…>     Nombres de simulations (défaut : 1,4)
+  --neighbor-search <…>     grid, brute ou les deux (défaut : grid,brute)
+  --backends <…>            gpu, cpu ou les deux (défaut : gpu,cpu ; gpu ignoré sans adaptateur)
+  --frames <n>              Frames mesurées par combinaison (défaut : 60)
+  --warmup <n>              Frames ignorées avant la mesure (défaut : 10)
+  --output <fichier.csv>    Rapport (défaut : bench_output/benchmark.csv)
+
+compute_ms inclut l'attente de la fin des passes GPU : le backend GPU est mesuré sans recouvrement
+entre frames, et ses chiffres sont comparables à ceux du CPU.";
+
+const CSV_HEADER: &str = "backend,neighbor_search,simulations,particles,types,frames,steps,\
+steps_per_second,compute_ms,readback_ms,food_ms\n";
+/// Frames accordées à un backend pour publier son premier état (compilation des shaders)
+const MAX_WAIT_FRAMES: u32 = 600;
+
+/// Une combinaison mesurée
+#[derive(Clone, Debug)]
+pub struct BenchCase {
+    pub backend: BackendKind,
+    pub neighbor_search: NeighborSearch,
+    pub simulations: usize,
+    pub particles: usize,
+    pub types: usize,
+}
+
+impl BenchCase {
+    fn config(&self, base: &SimulationConfig) -> SimulationConfig {
+        let particles_per_simulation = self.particles / self.simulations.max(1);
+
+        SimulationConfig {
+            backend: self.backend,
+            neighbor_search: self.neighbor_search,
+            simulation_count: self.simulations,
+            particles_per_simulation,
+            particle_count: particles_per_simulation * self.simulations,
+            particle_types: self.types,
+            // Aucune fin d'époque ne doit relancer la population pendant la mesure
+            epoch_duration: f32::INFINITY,
+            ..base.clone()
+        }
+    }
+}
+
+/// Options du benchmark, lues depuis la ligne de commande
+pub struct BenchOptions {
+    pub base_config: SimulationConfig,
+    pub cases: Vec<BenchCase>,
+    pub frames: u32,
+    pub warmup: u32,
+    pub output: PathBuf,
+}
+
+impl BenchOptions {
+    pub fn from_args(args: &[String], gpu_available: bool) -> Result<Self, String> {
+        let base_config = base_config(args)?;
+        let mut particles = vec![1000, 4000, 16000];
+        let mut types = vec![3, 6];
+        let mut simulations = vec![1, 4];
+        let mut searches = vec![NeighborSearch::SpatialHash, NeighborSearch::BruteForce];
+        let mut backends = vec![BackendKind::Gpu, BackendKind::Cpu];
+        let mut frames = 60;
+        let mut warmup = 10;
+        let mut output = PathBuf::from("bench_output/benchmark.csv");
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--bench" {
+                continue;
+            }
+
+            let value = iter.next().ok_or_else(|| format!("Valeur manquante pour {}", arg))?;
+            match arg.as_str() {
+                "--config" => {}
+                "--particles" => particles = parse_list(arg, value, parse)?,
+                "--types" => types = parse_list(arg, value, parse)?,
+                "--simulations" => simulations = parse_list(arg, value, parse)?,
+                "--neighbor-search" => searches = parse_list(arg, value, parse_neighbor_search)?,
+                "--backends" => backends = parse_list(arg, value, parse_backend)?,
+                "--frames" => frames = parse(arg, value)?,
+                "--warmup" => warmup = parse(arg, value)?,
+                "--output" => output = PathBuf::from(value),
+                _ => return Err(format!("Option inconnue : {}", arg)),
+            }
+        }
+
+        if !gpu_available && backends.contains(&BackendKind::Gpu) {
+            println!("⏭️ Backend GPU : skipped (aucun adaptateur GPU)");
+            backends.retain(|backend| *backend != BackendKind::Gpu);
+        }
+
+        let mut cases = Vec::new();
+        for &backend in &backends {
+            for &neighbor_search in &searches {
+                for &simulations in &simulations {
+                    for &particles in &particles {
+                        for &types in &types {
+                            let case = BenchCase { backend, neighbor_search, simulations, particles, types };
+                            // Refusée d'emblée plutôt qu'ajustée ou plantée en cours de mesure
+                            case.config(&base_config).validate()
+                                .map_err(|error| format!("Combinaison invalide ({} simulations, {} particules, {} types) : {}",
+                                                         simulations, particles, types, error))?;
+                            cases.push(case);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Self { base_config, cases, frames: frames.max(1), warmup, output })
+    }
+}
+
+fn parse_list<T>(name: &str, value: &str, parse_item: fn(&str, &str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    value.split(',').map(|item| parse_item(name, item.trim())).collect()
+}
+
+fn parse_neighbor_search(name: &str, value: &str) -> Result<NeighborSearch, String> {
+    match value {
+        "grid" => Ok(NeighborSearch::SpatialHash),
+        "brute" => Ok(NeighborSearch::BruteForce),
+        _ => Err(format!("Valeur invalide pour {} : {} (grid ou brute)", name, value)),
+    }
+}
+
+fn parse_backend(name: &str, value: &str) -> Result<BackendKind, String> {
+    match value {
+        "gpu" => Ok(BackendKind::Gpu),
+        "cpu" => Ok(BackendKind::Cpu),
+        _ => Err(format!("Valeur invalide pour {} : {} (gpu ou cpu)", name, value)),
+    }
+}
+
+/// Mesure chaque combinaison en passant par les vrais systèmes de simulation, puis écrit le rapport CSV
+pub fn run_bench(args: &[String]) -> AppExit {
+    let gpu_available = gpu_adapter_available();
+    let options = match BenchOptions::from_args(args, gpu_available) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            return AppExit::error();
+        }
+    };
+
+    println!("Starting benchmark: {} combinaisons, {} frames mesurées chacune", options.cases.len(), options.frames);
+
+    let mut app = App::new();
+    if options.cases.iter().any(|case| case.backend == BackendKind::Gpu) {
+        app
+            .add_plugins(
+                DefaultPlugins
+                    .set(WindowPlugin {
+                        primary_window: None,
+                        exit_condition: ExitCondition::DontExit,
+                        close_when_requested: false,
+                    })
+                    .disable::<WinitPlugin>(),
+            )
+            .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+            .add_plugins(SimulationCorePlugin)
+            .add_plugins(GpuBackendPlugin);
+    } else {
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(StatesPlugin)
+            .add_plugins(SimulationCorePlugin);
+    }
+
+    app
+        .init_state::<AppState>()
+        .add_plugins(BenchPlugin { options })
+        .run()
+}
+
+pub struct BenchPlugin {
+    pub options: BenchOptions,
+}
+
+impl Plugin for BenchPlugin {
+    fn build(&self, app: &mut App) {
+        // Un seul thread : rien ne s'intercale entre deux marqueurs de temps
+        app.edit_schedule(Update, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+        app.edit_schedule(PostUpdate, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+
+        app
+            // Un pas physique par frame, comme le mode headless
+            .insert_resource(TimeUpdateStrategy::ManualDuration(
+                Duration::from_secs_f32(PHYSICS_TIMESTEP),
+            ))
+            .insert_resource(BenchRun {
+                base_config: self.options.base_config.clone(),
+                cases: self.options.cases.clone(),
+                current: 0,
+                frames: self.options.frames,
+                warmup: self.options.warmup,
+                output: self.options.output.clone(),
+                waited_frames: 0,
+                warmup_frames: 0,
+                measured_frames: 0,
+                start_steps: 0,
+                rows: Vec::new(),
+            })
+            .init_resource::<BenchTimer>()
+            .add_systems(Update, (
+                begin_compute_timing.before(SimulationStepSet),
+                wait_for_gpu
+                    .after(step_simulation::<GpuBackend>)
+                    .before(end_compute_timing)
+                    .run_if(backend_is(BackendKind::Gpu)),
+                end_compute_timing
+                    .after(step_simulation::<CpuBackend>)
+                    .after(step_simulation::<GpuBackend>)
+                    .before(publish_snapshot::<CpuBackend>)
+                    .before(publish_snapshot::<GpuBackend>),
+                end_readback_timing
+                    .after(publish_snapshot::<CpuBackend>)
+                    .after(publish_snapshot::<GpuBackend>)
+                    .before(handle_food_interactions),
+                end_food_timing.after(handle_food_interactions),
+            ).run_if(in_state(AppState::Simulation)))
+            .add_systems(Last, (
+                start_next_case.run_if(not(in_state(AppState::Simulation))),
+                measure_case.run_if(in_state(AppState::Simulation)),
+            ));
+    }
+}
+
+/// Temps cumulés sur les frames mesurées
+#[derive(Resource)]
+pub struct BenchTimer {
+    pub measuring: bool,
+    /// Début de la mesure, pour le débit en pas par seconde
+    started: Instant,
+    mark: Instant,
+    pub compute: Duration,
+    pub readback: Duration,
+    pub food: Duration,
+}
+
+impl Default for BenchTimer {
+    fn default() -> Self {
+        Self {
+            measuring: false,
+            started: Instant::now(),
+            mark: Instant::now(),
+            compute: Duration::ZERO,
+            readback: Duration::ZERO,
+            food: Duration::ZERO,
+        }
+    }
+}
+
+impl BenchTimer {
+    /// Temps écoulé depuis le marqueur précédent, compté seulement pendant la mesure
+    fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now - self.mark;
+        self.mark = now;
+        if self.measuring { elapsed } else { Duration::ZERO }
+    }
+}
+
+fn begin_compute_timing(mut timer: ResMut<BenchTimer>) {
+    timer.mark = Instant::now();
+}
+
+/// Sans attente, seuls l'encodage et la soumission des passes GPU seraient chronométrés
+fn wait_for_gpu(backend: Option<Res<GpuBackend>>) {
+    if let Some(backend) = backend {
+        backend.wait_for_submissions();
+    }
+}
+
+fn end_compute_timing(mut timer: ResMut<BenchTimer>) {
+    let lap = timer.lap();
+    timer.compute += lap;
+}
+
+/// La relecture couvre `publish_snapshot`
+fn end_readback_timing(mut timer: ResMut<BenchTimer>) {
+    let lap = timer.lap();
+    timer.readback += lap;
+}
+
+fn end_food_timing(mut timer: ResMut<BenchTimer>) {
+    let lap = timer.lap();
+    timer.food += lap;
+}
+
+/// Résultat d'une combinaison
+struct BenchRow {
+    case: BenchCase,
+    /// Population effectivement simulée : les particules sont réparties également entre simulations
+    simulations: usize,
+    particles: usize,
+    frames: u32,
+    steps: u64,
+    elapsed: Duration,
+    compute: Duration,
+    readback: Duration,
+    food: Duration,
+}
+
+impl BenchRow {
+    fn csv_line(&self) -> String {
+        let per_frame = |duration: Duration| duration.as_secs_f64() * 1000.0 / self.frames as f64;
+        let steps_per_second = self.steps as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON);
+
+        format!("{:?},{:?},{},{},{},{},{},{:.1},{:.3},{:.3},{:.3}\n",
+                self.case.backend, self.case.neighbor_search, self.simulations, self.particles,
+                self.case.types, self.frames, self.steps, steps_per_second,
+                per_frame(self.compute), per_frame(self.readback), per_frame(self.food))
+    }
+}
+
+#[derive(Resource)]
+struct BenchRun {
+    base_config: SimulationConfig,
+    cases: Vec<BenchCase>,
+    current: usize,
+    frames: u32,
+    warmup: u32,
+    output: PathBuf,
+    /// Frames passées à attendre le premier état publié
+    waited_frames: u32,
+    warmup_frames: u32,
+    measured_frames: u32,
+    start_steps: u64,
+    rows: Vec<BenchRow>,
+}
+
+/// Lance la combinaison suivante : chacune repasse par le menu pour reconstruire entités et backend.
+/// Après la dernière, écrit le rapport et quitte.
+fn start_next_case(
+    mut commands: Commands,
+    mut run: ResMut<BenchRun>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let Some(case) = run.cases.get(run.current).cloned() else {
+        match write_report(&run.output, &run.rows) {
+            Ok(()) => {
+                println!("✅ Rapport écrit dans {}", run.output.display());
+                exit.write(AppExit::Success);
+            }
+            Err(error) => {
+                eprintln!("❌ Écriture du rapport impossible : {}", error);
+                exit.write(AppExit::error());
+            }
+        }
+        return;
+    };
+
+    println!("▶️ [{}/{}] {:?}", run.current + 1, run.cases.len(), case);
+    let config = case.config(&run.base_config);
+    commands.insert_resource(ParticleTypesConfig::new(config.particle_types));
+    commands.insert_resource(config);
+    run.waited_frames = 0;
+    run.warmup_frames = 0;
+    run.measured_frames = 0;
+    next_state.set(AppState::Simulation);
+}
+
+/// Attend le premier état publié, laisse passer l'échauffement puis mesure `frames` frames
+fn measure_case(
+    mut run: ResMut<BenchRun>,
+    mut timer: ResMut<BenchTimer>,
+    mut next_state: ResMut<NextState<AppState>>,
+    clock: Res<SimulationClock>,
+    snapshot: Res<ParticleSnapshot>,
+    sim_config: Res<SimulationConfig>,
+) {
+    // Le backend n'a encore rien publié : shaders en compilation ou upload en attente
+    if snapshot.positions.is_empty() {
+        run.waited_frames += 1;
+        if run.waited_frames > MAX_WAIT_FRAMES {
+            eprintln!("⚠️ Combinaison ignorée : aucun état publié après {} frames", MAX_WAIT_FRAMES);
+            run.current += 1;
+            next_state.set(AppState::MainMenu);
+        }
+        return;
+    }
+
+    if !timer.measuring {
+        run.warmup_frames += 1;
+        if run.warmup_frames > run.warmup {
+            *timer = BenchTimer { measuring: true, ..default() };
+            run.start_steps = clock.total_steps;
+        }
+        return;
+    }
+
+    run.measured_frames += 1;
+    if run.measured_frames < run.frames {
+        return;
+    }
+
+    let row = BenchRow {
+        case: run.cases[run.current].clone(),
+        simulations: sim_config.simulation_count,
+        particles: sim_config.particle_count,
+        frames: run.measured_frames,
+        steps: clock.total_steps - run.start_steps,
+        elapsed: timer.started.elapsed(),
+        compute: timer.compute,
+        readback: timer.readback,
+        food: timer.food,
+    };
+    print!("   {}", row.csv_line());
+    run.rows.push(row);
+
+    timer.measuring = false;
+    run.current += 1;
+    next_state.set(AppState::MainMenu);
+}
+
+fn write_report(output: &Path, rows: &[BenchRow]) -> std::io::Result<()> {
+    if let Some(dir) = output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut csv = String::from(CSV_HEADER);
+    for row in rows {
+        csv.push_str(&row.csv_line());
+    }
+    fs::write(output, csv)
+}
@@ -0,0 +1,358 @@
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use serde::Serialize;
+
+use crate::components::simulation::Simulation;
+use crate::globals::*;
+use crate::plugins::particle_life_plugin::SimulationCorePlugin;
+use crate::resources::{particle_config::*, physics_params::TypeProperties, simulation_config::*};
+use crate::states::app_state::AppState;
+use crate::systems::evolution_system::{EpochEnded, Generation, end_epoch, evaluate_fitness};
+use crate::systems::simulation_system::SimulationClock;
+
+const USAGE: &str = "\
+Usage: truc --headless [options]
+  --config <fichier.ron>    SimulationConfig de départ (champs absents = valeurs par défaut)
+  --epochs <n>              Nombre d'époques à simuler (défaut : 1)
+  --seconds <s>             Durée simulée, à la place de --epochs
+  --output <dossier>        Dossier des résultats (défaut : headless_output)
+  --seed <n>                Graine des tirages aléatoires (défaut : aléatoire, écrite dans config.ron)
+  --simulations <n>  --particles <n>  --types <n>  --food <n>
+  --world-size <x>  --force-range <x>  --time-scale <x>  --epoch-duration <s>  --dimension <2|3>
+  --selection <tournament|roulette|rank|truncation>  --tournament-size <n>  --truncation-ratio <x>
+  --crossover <uniform|row|point|blend>  --blend-alpha <x>  --mutation <gaussian|reset|swap>  --mutation-sigma <x>
+  --fitness <objectif=poids,...>  (objectifs : food, energy, clusters, spread, travel)";
+
+/// Durée d'une session headless, en temps simulé
+#[derive(Clone, Copy, Debug)]
+pub enum RunLength {
+    Epochs(u32),
+    Seconds(f32),
+}
+
+/// Options du mode headless, lues depuis la ligne de commande
+#[derive(Clone)]
+pub struct HeadlessOptions {
+    pub config: SimulationConfig,
+    pub length: RunLength,
+    pub output_dir: PathBuf,
+}
+
+impl HeadlessOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = base_config(args)?;
+        let mut length = RunLength::Epochs(1);
+        let mut output_dir = PathBuf::from("headless_output");
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--headless" {
+                continue;
+            }
+
+            let value = iter.next().ok_or_else(|| format!("Valeur manquante pour {}", arg))?;
+            match arg.as_str() {
+                "--config" => {}
+                "--epochs" => length = RunLength::Epochs(parse(arg, value)?),
+                "--seconds" => length = RunLength::Seconds(parse(arg, value)?),
+                "--output" => output_dir = PathBuf::from(value),
+                "--seed" => config.seed = parse(arg, value)?,
+                "--simulations" => config.simulation_count = parse(arg, value)?,
+                "--particles" => config.particle_count = parse(arg, value)?,
+                "--types" => config.particle_types = parse(arg, value)?,
+                "--food" => config.food_count = parse(arg, value)?,
+                "--world-size" => config.world_size = parse(arg, value)?,
+                "--force-range" => config.max_force_range = parse(arg, value)?,
+                "--time-scale" => config.time_scale = parse(arg, value)?,
+                "--epoch-duration" => config.epoch_duration = parse(arg, value)?,
+                "--dimension" => config.dimension = parse_dimension(arg, value)?,
+                "--selection" => config.selection = parse_selection(arg, value)?,
+                "--tournament-size" => config.tournament_size = parse(arg, value)?,
+                "--truncation-ratio" => config.truncation_ratio = parse(arg, value)?,
+                "--crossover" => config.crossover = parse_crossover(arg, value)?,
+                "--blend-alpha" => config.blend_alpha = parse(arg, value)?,
+                "--mutation" => config.mutation = parse_mutation(arg, value)?,
+                "--mutation-sigma" => config.mutation_sigma = parse(arg, value)?,
+                "--fitness" => config.fitness_weights = parse_fitness(arg, value)?,
+                _ => return Err(format!("Option inconnue : {}", arg)),
+            }
+        }
+
+        match length {
+            RunLength::Epochs(0) => return Err("--epochs doit valoir au moins 1".to_string()),
+            RunLength::Seconds(seconds) if seconds.is_nan() || seconds <= 0.0 => {
+                return Err(format!("Durée invalide pour --seconds : {} (doit être positive)", seconds));
+            }
+            _ => {}
+        }
+
+        // Un nombre de simulations hors bornes est refusé par `validate`
+        config.particles_per_simulation = config.particle_count.checked_div(config.simulation_count).unwrap_or(0);
+        config.validate()?;
+
+        // Pas d'adaptateur graphique sans fenêtre : la physique tourne sur CPU
+        if config.backend != BackendKind::Cpu {
+            println!("Mode headless : backend {} remplacé par CPU", config.backend.label());
+            config.backend = BackendKind::Cpu;
+        }
+
+        Ok(Self { config, length, output_dir })
+    }
+}
+
+/// Config lue depuis `--config`, ou par défaut. Le fichier sert de base : les autres options
+/// le surchargent quel que soit leur ordre.
+pub fn base_config(args: &[String]) -> Result<SimulationConfig, String> {
+    let Some(path) = option_value(args, "--config") else {
+        return Ok(SimulationConfig::default());
+    };
+
+    let text = fs::read_to_string(path).map_err(|e| format!("Lecture de {} impossible : {}", path, e))?;
+    ron::from_str(&text).map_err(|e| format!("Config {} invalide : {}", path, e))
+}
+
+pub fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a String> {
+    args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1))
+}
+
+pub fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Valeur invalide pour {} : {}", name, value))
+}
+
+pub fn parse_dimension(name: &str, value: &str) -> Result<Dimension, String> {
+    match value {
+        "2" => Ok(Dimension::Two),
+        "3" => Ok(Dimension::Three),
+        _ => Err(format!("Valeur invalide pour {} : {} (2 ou 3)", name, value)),
+    }
+}
+
+pub fn parse_selection(name: &str, value: &str) -> Result<SelectionMethod, String> {
+    match value {
+        "tournament" => Ok(SelectionMethod::Tournament),
+        "roulette" => Ok(SelectionMethod::Roulette),
+        "rank" => Ok(SelectionMethod::Rank),
+        "truncation" => Ok(SelectionMethod::Truncation),
+        _ => Err(format!("Valeur invalide pour {} : {} (tournament, roulette, rank ou truncation)", name, value)),
+    }
+}
+
+pub fn parse_crossover(name: &str, value: &str) -> Result<CrossoverMethod, String> {
+    match value {
+        "uniform" => Ok(CrossoverMethod::Uniform),
+        "row" => Ok(CrossoverMethod::RowSwap),
+        "point" => Ok(CrossoverMethod::SinglePoint),
+        "blend" => Ok(CrossoverMethod::Blend),
+        _ => Err(format!("Valeur invalide pour {} : {} (uniform, row, point ou blend)", name, value)),
+    }
+}
+
+pub fn parse_mutation(name: &str, value: &str) -> Result<MutationMethod, String> {
+    match value {
+        "gaussian" => Ok(MutationMethod::Gaussian),
+        "reset" => Ok(MutationMethod::Reset),
+        "swap" => Ok(MutationMethod::Swap),
+        _ => Err(format!("Valeur invalide pour {} : {} (gaussian, reset ou swap)", name, value)),
+    }
+}
+
+/// `food=1,energy=0.5` : les objectifs absents ont un poids nul
+pub fn parse_fitness(name: &str, value: &str) -> Result<FitnessWeights, String> {
+    let mut weights = FitnessWeights::default();
+    for objective in FitnessObjective::ALL {
+        *weights.weight_mut(objective) = 0.0;
+    }
+
+    for term in value.split(',') {
+        let (key, weight) = term.split_once('=')
+            .ok_or_else(|| format!("Valeur invalide pour {} : {} (objectif=poids attendu)", name, term))?;
+        let objective = FitnessObjective::ALL.into_iter()
+            .find(|objective| objective.key() == key.trim())
+            .ok_or_else(|| format!("Objectif inconnu pour {} : {}", name, key))?;
+        *weights.weight_mut(objective) = parse(name, weight.trim())?;
+    }
+    Ok(weights)
+}
+
+/// Lance une session sans fenêtre ni GPU et renvoie son code de sortie
+pub fn run_headless(args: &[String]) -> AppExit {
+    let options = match HeadlessOptions::from_args(args) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            return AppExit::error();
+        }
+    };
+
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugins(HeadlessPlugin { options })
+        .run()
+}
+
+pub struct HeadlessPlugin {
+    pub options: HeadlessOptions,
+}
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        let config = self.options.config.clone();
+
+        println!("Starting headless run: {} simulations, {} particles, {:?}, seed {}",
+                 config.simulation_count, config.particle_count, self.options.length, config.seed);
+
+        app
+            .add_plugins(StatesPlugin)
+            .insert_state(AppState::Simulation)
+            .insert_resource(ParticleTypesConfig::new(config.particle_types))
+            .insert_resource(config)
+            .insert_resource(HeadlessRun {
+                length: self.options.length,
+                output_dir: self.options.output_dir.clone(),
+                scores: Vec::new(),
+            })
+            // Chaque update avance exactement d'un pas physique, aussi vite que le CPU le permet
+            .insert_resource(TimeUpdateStrategy::ManualDuration(
+                std::time::Duration::from_secs_f32(PHYSICS_TIMESTEP),
+            ))
+            .add_plugins(SimulationCorePlugin)
+            .add_systems(Update, (record_epoch_scores, track_headless_run)
+                .chain()
+                .after(evaluate_fitness)
+                .before(end_epoch));
+    }
+}
+
+/// Progression de la session et scores relevés à la fin de chaque époque
+#[derive(Resource)]
+pub struct HeadlessRun {
+    pub length: RunLength,
+    pub output_dir: PathBuf,
+    /// (époque, simulation, score)
+    pub scores: Vec<(u32, u32, f32)>,
+}
+
+/// Relève les scores des époques closes par `end_epoch`
+fn record_epoch_scores(
+    mut run: ResMut<HeadlessRun>,
+    mut epoch_ended: EventReader<EpochEnded>,
+) {
+    for ended in epoch_ended.read() {
+        run.scores.extend(ended.scores.iter().map(|&(simulation, score)| (ended.generation, simulation, score)));
+    }
+}
+
+/// Termine la session avant que `end_epoch` ne fasse naître la génération suivante :
+/// les matrices écrites sont celles de la dernière génération évaluée
+fn track_headless_run(
+    mut run: ResMut<HeadlessRun>,
+    simulations: Query<&Simulation>,
+    generation: Res<Generation>,
+    clock: Res<SimulationClock>,
+    sim_config: Res<SimulationConfig>,
+    particle_config: Res<ParticleConfig>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let finished = match run.length {
+        RunLength::Epochs(epochs) => {
+            generation.number + 1 >= epochs && generation.remaining(&clock, sim_config.epoch_duration) <= 0.0
+        }
+        RunLength::Seconds(seconds) => clock.total_steps as f32 * PHYSICS_TIMESTEP >= seconds,
+    };
+
+    if !finished {
+        return;
+    }
+
+    // Dernière époque, éventuellement incomplète avec --seconds
+    for simulation in simulations.iter() {
+        run.scores.push((generation.number, simulation.id, simulation.score));
+    }
+
+    match write_results(&run, &sim_config, &particle_config) {
+        Ok(()) => {
+            println!("✅ Résultats écrits dans {}", run.output_dir.display());
+            exit.write(AppExit::Success);
+        }
+        Err(error) => {
+            eprintln!("❌ Écriture des résultats impossible : {}", error);
+            exit.write(AppExit::error());
+        }
+    }
+}
+
+/// Matrices finales, une par simulation (`num_types²` valeurs, ligne par ligne),
+/// avec les propriétés des types de chaque simulation
+#[derive(Serialize)]
+struct FinalMatrices {
+    num_types: u32,
+    force_matrices: Vec<Vec<f32>>,
+    type_properties: Vec<Vec<TypeProperties>>,
+}
+
+fn write_results(
+    run: &HeadlessRun,
+    sim_config: &SimulationConfig,
+    particle_config: &ParticleConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(&run.output_dir)?;
+
+    let mut scores = String::from("epoch,simulation,score\n");
+    for (epoch, simulation, score) in &run.scores {
+        scores.push_str(&format!("{},{},{}\n", epoch, simulation, score));
+    }
+    fs::write(run.output_dir.join("scores.csv"), scores)?;
+
+    let pretty = ron::ser::PrettyConfig::default();
+    let genomes = &particle_config.genomes;
+    let matrices = FinalMatrices {
+        num_types: particle_config.num_types,
+        force_matrices: genomes.iter().map(|genome| genome.force_matrix.clone()).collect(),
+        type_properties: genomes.iter().map(|genome| genome.type_properties.clone()).collect(),
+    };
+    fs::write(run.output_dir.join("matrices.ron"), ron::ser::to_string_pretty(&matrices, pretty.clone())?)?;
+    fs::write(run.output_dir.join("config.ron"), ron::ser::to_string_pretty(sim_config, pretty)?)?;
+
+    // Un fichier par simulation, importable depuis la fenêtre de la matrice
+    for (sim, genome) in genomes.iter().enumerate() {
+        genome.save(&run.output_dir.join(format!("genome_{}.ron", sim + 1)))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_args(args: &[&str]) -> Result<HeadlessOptions, String> {
+        let args: Vec<String> = ["--headless"].iter().chain(args).map(|arg| arg.to_string()).collect();
+        HeadlessOptions::from_args(&args)
+    }
+
+    #[test]
+    fn default_options_are_accepted() {
+        let options = from_args(&[]).unwrap();
+        assert!(matches!(options.length, RunLength::Epochs(1)));
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let too_many = (MAX_SIMULATIONS + 1).to_string();
+        for args in [
+            &["--simulations", "0"][..],
+            &["--simulations", &too_many],
+            &["--epochs", "0"],
+            &["--seconds", "0"],
+            &["--time-scale", "0"],
+            &["--time-scale", "NaN"],
+            &["--force-range", "-1"],
+        ] {
+            assert!(from_args(args).is_err(), "{:?} accepté", args);
+        }
+    }
+}
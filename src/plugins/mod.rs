@@ -1,2 +1,6 @@
+pub mod bench_plugin;
+pub mod headless_plugin;
+pub mod parity_plugin;
 pub mod particle_life_plugin;
+pub mod particle_render_plugin;
 mod ui_plugin;
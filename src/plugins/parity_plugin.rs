@@ -0,0 +1,271 @@
+use std::time::Duration;
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::prelude::*;
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+
+use crate::globals::*;
+use crate::physics::backend::SimulationBackend;
+use crate::physics::cpu_backend::CpuBackend;
+use crate::physics::gpu_backend::GpuBackend;
+use crate::physics::kernel::ForceField;
+use crate::physics::parity::{ParityReport, compare_snapshots};
+use crate::plugins::headless_plugin::{base_config, parse, parse_dimension};
+use crate::plugins::particle_life_plugin::gpu_adapter_available;
+use crate::resources::{particle_config::*, particle_snapshot::*, physics_params::*, simulation_config::*, simulation_rng::*};
+
+const USAGE: &str = "\
+Usage: truc --parity [options]
+  --config <fichier.ron>    SimulationConfig de départ (modèle de force, bords, grille…)
+  --steps <n>               Nombre de pas comparés (défaut : 16)
+  --tolerance <x>           Écart maximal toléré en position et en vitesse (défaut : 1e-3)
+  --seed <n>  --simulations <n>  --particles <n>  --types <n>  --dimension <2|3>";
+
+const DEFAULT_PARITY_STEPS: u32 = 16;
+const DEFAULT_PARITY_TOLERANCE: f32 = 1e-3;
+const DEFAULT_PARITY_PARTICLES: usize = 512;
+const DEFAULT_PARITY_SIMULATIONS: usize = 2;
+/// Frames accordées au GPU pour rendre chaque lot
+const MAX_WAIT_FRAMES: u32 = 600;
+/// Nombre de particules divergentes détaillées dans le rapport
+const MAX_REPORTED_PARTICLES: usize = 10;
+
+/// Options du contrôle de parité, lues depuis la ligne de commande
+pub struct ParityOptions {
+    pub config: SimulationConfig,
+    pub steps: u32,
+    pub tolerance: f32,
+}
+
+impl ParityOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = base_config(args)?;
+        let mut steps = DEFAULT_PARITY_STEPS;
+        let mut tolerance = DEFAULT_PARITY_TOLERANCE;
+
+        // Un petit jeu de particules suffit à exercer toutes les branches du shader
+        if !args.iter().any(|arg| arg == "--config") {
+            config.simulation_count = DEFAULT_PARITY_SIMULATIONS;
+            config.particle_count = DEFAULT_PARITY_PARTICLES;
+        }
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--parity" {
+                continue;
+            }
+
+            let value = iter.next().ok_or_else(|| format!("Valeur manquante pour {}", arg))?;
+            match arg.as_str() {
+                "--config" => {}
+                "--steps" => steps = parse(arg, value)?,
+                "--tolerance" => tolerance = parse(arg, value)?,
+                "--seed" => config.seed = parse(arg, value)?,
+                "--simulations" => config.simulation_count = parse(arg, value)?,
+                "--particles" => config.particle_count = parse(arg, value)?,
+                "--types" => config.particle_types = parse(arg, value)?,
+                "--dimension" => config.dimension = parse_dimension(arg, value)?,
+                _ => return Err(format!("Option inconnue : {}", arg)),
+            }
+        }
+
+        config.simulation_count = config.simulation_count.clamp(1, MAX_SIMULATIONS);
+        config.particles_per_simulation = config.particle_count / config.simulation_count;
+        config.particle_count = config.particles_per_simulation * config.simulation_count;
+        config.validate()?;
+
+        Ok(Self { config, steps: steps.max(1), tolerance })
+    }
+}
+
+/// Fait avancer le même état initial sur GPU et sur CPU, puis compare les particules une à une.
+/// Sans adaptateur GPU, le contrôle est sauté plutôt que d'échouer.
+pub fn run_parity(args: &[String]) -> AppExit {
+    let options = match ParityOptions::from_args(args) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            return AppExit::error();
+        }
+    };
+
+    if !gpu_adapter_available() {
+        println!("⏭️ Parité GPU/CPU : skipped (aucun adaptateur GPU)");
+        return AppExit::Success;
+    }
+
+    let config = options.config;
+    let total_steps = options.steps * config.substeps.max(1);
+    println!("Starting GPU/CPU parity check: {} simulations, {} particles, {} steps, seed {}",
+             config.simulation_count, config.particle_count, total_steps, config.seed);
+
+    let (particle_config, initial) = initial_state(&config);
+
+    // Référence CPU, calculée d'une traite
+    let mut cpu = CpuBackend::new(&particle_config, &config);
+    cpu.upload_particles(&initial.positions, &initial.velocities, &particle_config.padded_simulation_ranges());
+    cpu.step(total_steps, 1);
+    let reference = cpu.poll_snapshot().unwrap_or_default();
+
+    App::new()
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                })
+                .disable::<WinitPlugin>(),
+        )
+        .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+        .insert_resource(particle_config)
+        .insert_resource(config)
+        .insert_resource(ParityRun {
+            initial,
+            reference,
+            tolerance: options.tolerance,
+            total_steps,
+            remaining_steps: total_steps,
+            waited_frames: 0,
+        })
+        .add_plugins(ParityPlugin)
+        .run()
+}
+
+/// Matrices tirées comme à l'entrée en simulation, particules réparties dans tout le monde
+/// avec une vitesse initiale pour exercer aussi la friction et les bords
+fn initial_state(config: &SimulationConfig) -> (ParticleConfig, ParticleSnapshot) {
+    let mut particle_config = ParticleConfig {
+        num_particles: config.particle_count as u32,
+        world_size: config.world_size,
+        ..default()
+    };
+    particle_config.resize(config.simulation_count as u32, config.particle_types as u32);
+
+    let mut rngs = SimulationRngs::new(config.seed, config.simulation_count as u32);
+    particle_config.generate_random_forces(&mut rngs);
+
+    let mut initial = ParticleSnapshot::default();
+
+    for sim in 0..config.simulation_count as u32 {
+        let offset = initial.positions.len() as u32;
+        particle_config.simulation_ranges.push([offset, config.particles_per_simulation as u32]);
+
+        let Some(rng) = rngs.get_mut(sim) else {
+            continue;
+        };
+
+        for i in 0..config.particles_per_simulation as u32 {
+            let particle_type = (offset + i) % config.particle_types as u32;
+            let position = config.dimension.random_point(&mut rng.spawn, config.world_size);
+            let velocity = config.dimension.random_point(&mut rng.spawn, 2.0);
+
+            initial.positions.push([position.x, position.y, position.z, particle_type as f32]);
+            initial.velocities.push([velocity.x, velocity.y, velocity.z, sim as f32]);
+        }
+    }
+
+    (particle_config, initial)
+}
+
+pub struct ParityPlugin;
+
+impl Plugin for ParityPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, setup_gpu_parity)
+            .add_systems(Update, run_gpu_parity);
+    }
+}
+
+#[derive(Resource)]
+struct ParityRun {
+    initial: ParticleSnapshot,
+    reference: ParticleSnapshot,
+    tolerance: f32,
+    total_steps: u32,
+    remaining_steps: u32,
+    /// Frames passées à attendre le lot en cours
+    waited_frames: u32,
+}
+
+/// Backend construit à partir de `ParticleConfig`, chargé avec l'état initial
+fn setup_gpu_parity(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+    run: Res<ParityRun>,
+    particle_config: Res<ParticleConfig>,
+    sim_config: Res<SimulationConfig>,
+) {
+    let mut backend = GpuBackend::new(&device, &queue, &particle_config, &sim_config);
+    backend.upload_particles(&run.initial.positions, &run.initial.velocities, &particle_config.padded_simulation_ranges());
+    // Un lot vide : le premier état relu est l'état initial
+    backend.step(0, 1);
+    commands.insert_resource(backend);
+}
+
+fn run_gpu_parity(
+    mut backend: ResMut<GpuBackend>,
+    mut run: ResMut<ParityRun>,
+    particle_config: Res<ParticleConfig>,
+    sim_config: Res<SimulationConfig>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let Some(snapshot) = backend.poll_snapshot() else {
+        run.waited_frames += 1;
+        if run.waited_frames > MAX_WAIT_FRAMES {
+            eprintln!("❌ Parité GPU/CPU : aucun état relu après {} frames", MAX_WAIT_FRAMES);
+            exit.write(AppExit::error());
+        }
+        return;
+    };
+    run.waited_frames = 0;
+
+    if run.remaining_steps == 0 {
+        let params = PhysicsParams::from(&*sim_config);
+        let force_matrix = particle_config.flattened_force_matrices();
+        let type_properties = particle_config.flattened_type_properties();
+        let field = ForceField {
+            params: &params,
+            force_matrix: &force_matrix,
+            type_properties: &type_properties,
+            num_types: particle_config.num_types,
+            world_size: particle_config.world_size,
+        };
+
+        let report = compare_snapshots(&field, &run.reference, &snapshot, run.tolerance);
+        print_report(&report, run.total_steps);
+        exit.write(if report.matches() { AppExit::Success } else { AppExit::error() });
+        return;
+    }
+
+    // Même découpage en lots que la simulation
+    let steps = run.remaining_steps.min(MAX_STEPS_PER_FRAME);
+    run.remaining_steps -= steps;
+    backend.step(steps, 1);
+}
+
+fn print_report(report: &ParityReport, steps: u32) {
+    if report.matches() {
+        println!("✅ Parité GPU/CPU : {} particules conformes après {} pas (écart max position {:.2e}, vitesse {:.2e})",
+                 report.particles, steps, report.max_position_error, report.max_velocity_error);
+        return;
+    }
+
+    println!("❌ Parité GPU/CPU : {}/{} particules au-delà de la tolérance {:.0e} après {} pas",
+             report.divergent.len(), report.particles, report.tolerance, steps);
+    if report.missing > 0 {
+        println!("   {} particules présentes dans un seul des deux états", report.missing);
+    }
+    for divergence in report.divergent.iter().take(MAX_REPORTED_PARTICLES) {
+        println!("   particule {} (simulation {}) : Δposition {:.2e}, Δvitesse {:.2e}",
+                 divergence.index, divergence.simulation, divergence.position_error, divergence.velocity_error);
+    }
+    if report.divergent.len() > MAX_REPORTED_PARTICLES {
+        println!("   … et {} autres", report.divergent.len() - MAX_REPORTED_PARTICLES);
+    }
+    println!("   Écart max : position {:.2e}, vitesse {:.2e}", report.max_position_error, report.max_velocity_error);
+}
@@ -1,155 +1,126 @@
 use bevy::prelude::*;
-use bevy::reflect::TypePath;
-use bevy_app_compute::prelude::*;
-use rand::Rng;
-
-use crate::components::particle::*;
-use crate::resources::{particle_config::*, simulation_config::*};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::settings::WgpuLimits;
+use bevy::tasks::block_on;
+
+use crate::globals::*;
+use crate::physics::{cpu_backend::CpuBackend, gpu_backend::GpuBackend};
+use crate::plugins::particle_render_plugin::ParticleRenderPlugin;
+use crate::resources::{particle_config::*, particle_snapshot::*, simulation_config::*, simulation_rng::*};
 use crate::states::app_state::AppState;
-use crate::systems::{simulation_system::*, food_system::*, viewport_system::*};
+use crate::systems::{simulation_system::*, evolution_system::*, food_system::*, viewport_system::*};
 
-pub struct ParticleLifePlugin;
+/// Simulation sans rendu ni GPU : création des entités, backend CPU, nourriture.
+/// Utilisé seul par le mode headless, et inclus par `ParticleLifePlugin`.
+pub struct SimulationCorePlugin;
 
-impl Plugin for ParticleLifePlugin {
+impl Plugin for SimulationCorePlugin {
     fn build(&self, app: &mut App) {
         app
             .init_resource::<ParticleConfig>()
-            .add_plugins(AppComputeWorkerPlugin::<ParticleComputeWorker>::default())
+            .init_resource::<BackendSync>()
+            .init_resource::<ParticleSpawner>()
+            .init_resource::<SimulationClock>()
+            .init_resource::<ParticleSnapshot>()
+            .init_resource::<PreviousSnapshot>()
+            .init_resource::<SimulationRngs>()
+            .init_resource::<Generation>()
+            .init_resource::<FitnessTracker>()
+            .add_event::<EpochEnded>()
             .add_systems(OnEnter(AppState::Simulation), (
                 setup_simulations_from_config,
+                rebuild_cpu_backend
+                    .after(setup_simulations_from_config)
+                    .run_if(backend_is(BackendKind::Cpu)),
+            ))
+            .add_systems(OnExit(AppState::Simulation), (reset_backend_sync, reset_generation))
+            .add_systems(Update, (
+                handle_food_interactions,
+                evaluate_fitness
+                    .after(SimulationStepSet)
+                    .after(handle_food_interactions),
+                // Fin d'époque : classement, reproduction puis relance de la population
+                (end_epoch, breed_generation, restart_generation, respawn_food_on_epoch, reset_fitness)
+                    .chain()
+                    .after(evaluate_fitness),
+            ).run_if(in_state(AppState::Simulation)));
+
+        add_backend_systems::<CpuBackend>(app, BackendKind::Cpu);
+    }
+}
+
+pub struct ParticleLifePlugin;
+
+impl Plugin for ParticleLifePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(SimulationCorePlugin)
+            .add_plugins(GpuBackendPlugin)
+            .add_plugins(ParticleRenderPlugin)
+            .add_systems(OnEnter(AppState::Simulation), (
                 setup_viewports,
                 setup_lighting,
             ))
-            .add_systems(Update,
-                         initialize_gpu_data.run_if(in_state(AppState::Simulation))
-            )
             .add_systems(Update, (
-                update_particle_simulation,
-                update_particle_visualization.after(update_particle_simulation),
-                handle_food_interactions,
+                check_spatial_grid.after(SimulationStepSet),
                 display_scores,
                 update_viewports_on_resize,
             ).run_if(in_state(AppState::Simulation)));
     }
 }
 
-#[derive(TypePath)]
-struct ParticleComputeShader;
+/// Backend compute shader, à ajouter après `SimulationCorePlugin` et le `RenderPlugin`
+pub struct GpuBackendPlugin;
 
-impl ComputeShader for ParticleComputeShader {
-    fn shader() -> ShaderRef {
-        "shaders/particle_compute.wgsl".into()
+impl Plugin for GpuBackendPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(AppState::Simulation), rebuild_gpu_backend
+                .after(setup_simulations_from_config)
+                .run_if(backend_is(BackendKind::Gpu)));
+
+        add_backend_systems::<GpuBackend>(app, BackendKind::Gpu);
     }
 }
 
-#[derive(Resource)]
-pub struct ParticleComputeWorker;
-
-impl ComputeWorker for ParticleComputeWorker {
-    fn build(world: &mut World) -> AppComputeWorker<Self> {
-        // Utiliser la configuration du menu si disponible
-        let (num_particles, world_size, num_types, force_matrix) = if let Some(sim_config) = world.get_resource::<SimulationConfig>() {
-            (
-                sim_config.particle_count as u32,
-                sim_config.world_size,
-                sim_config.particle_types as u32,
-                generate_random_force_matrix(sim_config.particle_types),
-            )
-        } else {
-            // Valeurs par défaut
-            (
-                crate::globals::DEFAULT_PARTICLE_COUNT as u32,
-                crate::globals::DEFAULT_WORLD_SIZE,
-                crate::globals::DEFAULT_PARTICLE_TYPES as u32,
-                generate_random_force_matrix(crate::globals::DEFAULT_PARTICLE_TYPES),
-            )
-        };
-
-        let positions = vec![[0.0f32; 4]; num_particles as usize];
-        let velocities = vec![[0.0f32; 4]; num_particles as usize];
-
-        println!("Initializing {} particles with {} types", num_particles, num_types);
-
-        AppComputeWorkerBuilder::new(world)
-            .add_uniform("num_particles", &num_particles)
-            .add_uniform("dt", &(crate::globals::PHYSICS_TIMESTEP))
-            .add_uniform("world_size", &world_size)
-            .add_uniform("num_types", &num_types)
-            .add_staging("positions", &positions)
-            .add_staging("velocities", &velocities)
-            .add_staging("new_positions", &positions)
-            .add_staging("new_velocities", &velocities)
-            .add_staging("force_matrix", &force_matrix)
-            .add_pass::<ParticleComputeShader>(
-                [((num_particles + 63) / 64) as u32, 1, 1],
-                &["num_particles", "dt", "world_size", "num_types",
-                    "positions", "velocities", "new_positions", "new_velocities", "force_matrix"]
-            )
-            .add_swap("positions", "new_positions")
-            .add_swap("velocities", "new_velocities")
-            .build()
-    }
+/// Le RenderPlugin panique sans adaptateur : les modes sans fenêtre le cherchent avant de construire l'app
+pub fn gpu_adapter_available() -> bool {
+    // Mêmes backends que le RenderPlugin par défaut
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::from_env().unwrap_or(wgpu::Backends::PRIMARY),
+        ..default()
+    });
+
+    block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).is_some()
 }
 
-fn generate_random_force_matrix(num_types: usize) -> Vec<f32> {
-    let mut rng = rand::rng();
-    let matrix_size = num_types * num_types;
-
-    (0..matrix_size)
-        .map(|i| {
-            let type_a = i / num_types;
-            let type_b = i % num_types;
-
-            if type_a == type_b {
-                // Auto-répulsion pour éviter l'agglomération
-                rng.random_range(-1.0..=-0.1)
-            } else {
-                // Forces variées entre types différents
-                rng.random_range(-2.0..=2.0)
-            }
-        })
-        .collect()
+/// Plus grande population que le device peut simuler : chaque buffer par particule (16 octets
+/// pour les positions et les vélocités) doit tenir dans une liaison de stockage
+pub fn gpu_particle_capacity(limits: &WgpuLimits) -> usize {
+    let per_binding = (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size)
+        / std::mem::size_of::<[f32; 4]>() as u64;
+    let per_dispatch = (limits.max_compute_workgroups_per_dimension as u64).pow(2) * WORKGROUP_SIZE as u64;
+    per_binding.min(per_dispatch).min(usize::MAX as u64) as usize
 }
 
-// Reste du code identique...
-fn update_particle_simulation(
-    mut compute_worker: ResMut<AppComputeWorker<ParticleComputeWorker>>,
-    time: Res<Time>,
-    mut timer: Local<Timer>,
+/// Reconstruit le backend (buffers, pipelines, dispatch) pour la configuration choisie dans le menu.
+/// `ParticleConfig` est dérivée de la `SimulationConfig` active par `setup_simulations_from_config`.
+fn rebuild_gpu_backend(
+    mut commands: Commands,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+    particle_config: Res<ParticleConfig>,
+    sim_config: Res<SimulationConfig>,
 ) {
-    if timer.duration().is_zero() {
-        *timer = Timer::from_seconds(crate::globals::PHYSICS_TIMESTEP, TimerMode::Repeating);
-    }
-
-    timer.tick(time.delta());
-
-    if !timer.just_finished() {
-        return;
-    }
-
-    if !compute_worker.ready() {
-        return;
-    }
-
-    compute_worker.execute();
+    commands.insert_resource(GpuBackend::new(&device, &queue, &particle_config, &sim_config));
 }
 
-fn update_particle_visualization(
-    compute_worker: Res<AppComputeWorker<ParticleComputeWorker>>,
-    mut query: Query<(&LifeParticle, &mut Transform)>,
+fn rebuild_cpu_backend(
+    mut commands: Commands,
+    particle_config: Res<ParticleConfig>,
+    sim_config: Res<SimulationConfig>,
 ) {
-    if !compute_worker.ready() {
-        return;
-    }
-
-    let positions: Vec<[f32; 4]> = compute_worker.read_vec("positions");
-
-    for (particle, mut transform) in query.iter_mut() {
-        if let Some(pos) = positions.get(particle.index as usize) {
-            let new_pos = Vec3::new(pos[0], pos[1], pos[2]);
-            transform.translation = new_pos;
-        }
-    }
+    commands.insert_resource(CpuBackend::new(&particle_config, &sim_config));
 }
 
 fn setup_lighting(mut commands: Commands) {
@@ -160,6 +131,7 @@ fn setup_lighting(mut commands: Commands) {
             ..default()
         },
         Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -0.4, -0.7, 0.0)),
+        StateScoped(AppState::Simulation),
     ));
 
     commands.insert_resource(AmbientLight {
@@ -0,0 +1,440 @@
+use bevy::core_pipeline::core_3d::Transparent3d;
+use bevy::ecs::system::{lifetimeless::*, SystemParamItem};
+use bevy::pbr::{MeshPipeline, MeshPipelineKey, SetMeshViewBindGroup};
+use bevy::prelude::*;
+use bevy::render::{
+    extract_component::ExtractComponentPlugin,
+    mesh::{allocator::MeshAllocator, MeshVertexBufferLayoutRef, RenderMesh, RenderMeshBufferInfo},
+    render_asset::RenderAssets,
+    render_phase::{
+        AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand, RenderCommandResult,
+        SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+    },
+    render_resource::{
+        binding_types::{storage_buffer_read_only_sized, uniform_buffer_sized},
+        *,
+    },
+    renderer::{RenderDevice, RenderQueue},
+    sync_world::MainEntity,
+    view::ExtractedView,
+    Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+};
+use bytemuck::{Pod, Zeroable};
+
+use crate::components::particle::ParticleInstances;
+use crate::physics::gpu_backend::GpuBackend;
+use crate::resources::{particle_snapshot::*, simulation_config::*};
+use crate::systems::{simulation_system::BackendSync, viewport_system::SimulationCamera};
+
+const SHADER_ASSET_PATH: &str = "shaders/particle_render.wgsl";
+
+/// Rendu des particules sans entité par particule : chaque simulation est dessinée en un seul
+/// appel instancié, dont le vertex shader lit les positions dans les buffers du backend.
+/// Avec le backend GPU, ce sont les buffers du compute shader eux-mêmes ; avec le backend CPU,
+/// le dernier état publié y est recopié.
+pub struct ParticleRenderPlugin;
+
+impl Plugin for ParticleRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<ParticleInstances>::default(),
+            ExtractComponentPlugin::<SimulationCamera>::default(),
+        ));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<ExtractedParticles>()
+            .init_resource::<ParticleRenderBuffers>()
+            .init_resource::<SpecializedMeshPipelines<ParticlePipeline>>()
+            .add_render_command::<Transparent3d, DrawParticles>()
+            .add_systems(ExtractSchedule, (extract_particle_state, extract_particle_colors))
+            .add_systems(Render, (
+                prepare_particle_bind_group.in_set(RenderSet::PrepareBindGroups),
+                queue_particle_instances.in_set(RenderSet::QueueMeshes),
+            ));
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<ParticlePipeline>();
+    }
+}
+
+/// Paramètres de rendu (uniform `render_params` de particle_render.wgsl), dans l'ordre du struct WGSL
+#[repr(C)]
+#[derive(Clone, Copy, Default, Pod, Zeroable)]
+struct ParticleRenderParams {
+    /// Avancement entre les positions d'avant le dernier lot de pas et les dernières
+    blend: f32,
+    half_world: f32,
+}
+
+/// Couleur d'un type, telle que définie par `ParticleTypesConfig`
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct ParticleTypeColor {
+    base: [f32; 4],
+    emissive: [f32; 4],
+}
+
+/// Dernières positions publiées par le backend CPU et les précédentes (x, y, z, type)
+type CpuPositions = (Vec<[f32; 4]>, Vec<[f32; 4]>);
+
+/// Ce que le monde principal transmet au rendu à chaque frame
+#[derive(Resource, Default)]
+struct ExtractedParticles {
+    /// Buffers `positions` et `previous_positions` du worker, avec le backend GPU
+    gpu_buffers: Option<(Buffer, Buffer)>,
+    /// Dernier état publié par le backend CPU et le précédent, seulement s'ils ont changé
+    cpu_upload: Option<CpuPositions>,
+    /// Couleurs des types, seulement si elles ont changé
+    colors: Option<Vec<ParticleTypeColor>>,
+    params: ParticleRenderParams,
+}
+
+/// Buffers et bind group du rendu, conservés d'une frame à l'autre
+#[derive(Resource, Default)]
+struct ParticleRenderBuffers {
+    /// Copie des états du backend CPU, inutilisée avec le backend GPU
+    cpu_positions: Option<Buffer>,
+    cpu_previous: Option<Buffer>,
+    colors: Option<Buffer>,
+    params: Option<Buffer>,
+    bind_group: Option<BindGroup>,
+}
+
+fn extract_particle_state(
+    mut extracted: ResMut<ExtractedParticles>,
+    sim_config: Extract<Option<Res<SimulationConfig>>>,
+    backend_sync: Extract<Res<BackendSync>>,
+    time: Extract<Res<Time<Real>>>,
+    gpu_backend: Extract<Option<Res<GpuBackend>>>,
+    snapshot: Extract<Res<ParticleSnapshot>>,
+    previous: Extract<Res<PreviousSnapshot>>,
+) {
+    let Some(sim_config) = sim_config.as_ref() else {
+        return;
+    };
+
+    let blend = if sim_config.render_interpolation {
+        backend_sync.blend_factor(time.elapsed_secs_f64())
+    } else {
+        1.0
+    };
+    extracted.params = ParticleRenderParams {
+        blend,
+        half_world: sim_config.world_size * 0.5,
+    };
+
+    extracted.gpu_buffers = None;
+    // Les buffers GPU ne contiennent rien tant que la population n'est pas envoyée
+    if !backend_sync.initialized {
+        return;
+    }
+
+    match sim_config.backend {
+        BackendKind::Gpu => {
+            // Le backend n'est jamais relu pour le rendu : seuls ses buffers sont partagés
+            extracted.gpu_buffers = gpu_backend.as_ref().map(|backend| {
+                (backend.positions_buffer().clone(), backend.previous_positions_buffer().clone())
+            });
+        }
+        BackendKind::Cpu => {
+            if snapshot.is_changed() && !snapshot.positions.is_empty() {
+                // Au premier état publié, il n'y a rien à interpoler
+                let previous = if previous.positions.len() == snapshot.positions.len() {
+                    previous.positions.clone()
+                } else {
+                    snapshot.positions.clone()
+                };
+                extracted.cpu_upload = Some((snapshot.positions.clone(), previous));
+            }
+        }
+    }
+}
+
+fn extract_particle_colors(
+    mut extracted: ResMut<ExtractedParticles>,
+    particle_types: Extract<Option<Res<ParticleTypesConfig>>>,
+) {
+    let Some(particle_types) = particle_types.as_ref().filter(|types| types.is_changed()) else {
+        return;
+    };
+
+    extracted.colors = Some((0..particle_types.num_types())
+        .map(|type_index| {
+            let (color, emissive) = particle_types.get_color_for_type(type_index);
+            ParticleTypeColor {
+                base: color.to_linear().to_f32_array(),
+                emissive: emissive.to_f32_array(),
+            }
+        })
+        .collect());
+}
+
+/// Remplace le contenu d'un buffer, recréé seulement si sa taille change
+fn write_buffer(
+    render_device: &RenderDevice,
+    render_queue: &RenderQueue,
+    buffer: Option<Buffer>,
+    contents: &[u8],
+    usage: BufferUsages,
+    label: &'static str,
+) -> Buffer {
+    match buffer {
+        Some(buffer) if buffer.size() == contents.len() as u64 => {
+            render_queue.write_buffer(&buffer, 0, contents);
+            buffer
+        }
+        _ => render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some(label),
+            contents,
+            usage: usage | BufferUsages::COPY_DST,
+        }),
+    }
+}
+
+fn prepare_particle_bind_group(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipeline: Res<ParticlePipeline>,
+    mut extracted: ResMut<ExtractedParticles>,
+    mut buffers: ResMut<ParticleRenderBuffers>,
+) {
+    let buffers = &mut *buffers;
+
+    if let Some(colors) = extracted.colors.take().filter(|colors| !colors.is_empty()) {
+        buffers.colors = Some(write_buffer(
+            &render_device, &render_queue, buffers.colors.take(), bytemuck::cast_slice(&colors),
+            BufferUsages::STORAGE, "particle_type_colors",
+        ));
+    }
+
+    buffers.params = Some(write_buffer(
+        &render_device, &render_queue, buffers.params.take(), bytemuck::bytes_of(&extracted.params),
+        BufferUsages::UNIFORM, "particle_render_params",
+    ));
+
+    if let Some((positions, previous)) = extracted.cpu_upload.take() {
+        buffers.cpu_positions = Some(write_buffer(
+            &render_device, &render_queue, buffers.cpu_positions.take(), bytemuck::cast_slice(&positions),
+            BufferUsages::STORAGE, "particle_positions",
+        ));
+        buffers.cpu_previous = Some(write_buffer(
+            &render_device, &render_queue, buffers.cpu_previous.take(), bytemuck::cast_slice(&previous),
+            BufferUsages::STORAGE, "particle_previous_positions",
+        ));
+    }
+
+    let positions = match &extracted.gpu_buffers {
+        Some((positions, previous)) => Some((positions, previous)),
+        None => buffers.cpu_positions.as_ref().zip(buffers.cpu_previous.as_ref()),
+    };
+
+    buffers.bind_group = match (positions, &buffers.colors, &buffers.params) {
+        (Some((positions, previous)), Some(colors), Some(params)) => Some(render_device.create_bind_group(
+            "particle_render_bind_group",
+            &pipeline.layout,
+            &BindGroupEntries::sequential((
+                positions.as_entire_binding(),
+                previous.as_entire_binding(),
+                colors.as_entire_binding(),
+                params.as_entire_binding(),
+            )),
+        )),
+        _ => None,
+    };
+}
+
+/// Un élément de phase par simulation et par caméra de cette simulation
+fn queue_particle_instances(
+    draw_functions: Res<DrawFunctions<Transparent3d>>,
+    (pipeline, mut pipelines, pipeline_cache): (
+        Res<ParticlePipeline>,
+        ResMut<SpecializedMeshPipelines<ParticlePipeline>>,
+        Res<PipelineCache>,
+    ),
+    meshes: Res<RenderAssets<RenderMesh>>,
+    instances: Query<(Entity, &MainEntity, &ParticleInstances)>,
+    mut phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    views: Query<(&ExtractedView, &Msaa, &SimulationCamera)>,
+) {
+    let draw_particles = draw_functions.read().id::<DrawParticles>();
+
+    for (view, msaa, camera) in &views {
+        let Some(phase) = phases.get_mut(&view.retained_view_entity) else {
+            continue;
+        };
+
+        let view_key = MeshPipelineKey::from_msaa_samples(msaa.samples()) | MeshPipelineKey::from_hdr(view.hdr);
+        let rangefinder = view.rangefinder3d();
+
+        for (entity, main_entity, particles) in &instances {
+            if particles.simulation_id != camera.simulation_id || particles.count == 0 {
+                continue;
+            }
+            let Some(mesh) = meshes.get(&particles.mesh) else {
+                continue;
+            };
+
+            let key = view_key | MeshPipelineKey::from_primitive_topology(mesh.primitive_topology());
+            let Ok(pipeline_id) = pipelines.specialize(&pipeline_cache, &pipeline, key, &mesh.layout) else {
+                continue;
+            };
+
+            phase.add(Transparent3d {
+                entity: (entity, *main_entity),
+                pipeline: pipeline_id,
+                draw_function: draw_particles,
+                distance: rangefinder.distance_translation(&Vec3::ZERO),
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::None,
+                indexed: mesh.indexed(),
+            });
+        }
+    }
+}
+
+/// Pipeline de mesh de Bevy, dont le groupe du mesh est remplacé par les buffers des particules
+#[derive(Resource)]
+struct ParticlePipeline {
+    shader: Handle<Shader>,
+    mesh_pipeline: MeshPipeline,
+    layout: BindGroupLayout,
+}
+
+impl FromWorld for ParticlePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "particle_render_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::VERTEX,
+                (
+                    storage_buffer_read_only_sized(false, None),
+                    storage_buffer_read_only_sized(false, None),
+                    storage_buffer_read_only_sized(false, None),
+                    uniform_buffer_sized(false, None),
+                ),
+            ),
+        );
+
+        Self {
+            shader: world.load_asset(SHADER_ASSET_PATH),
+            mesh_pipeline: world.resource::<MeshPipeline>().clone(),
+            layout,
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for ParticlePipeline {
+    type Key = MeshPipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.mesh_pipeline.specialize(key, layout)?;
+
+        // Groupe 0 : la vue, comme tout mesh ; groupe 1 : positions et couleurs des particules
+        descriptor.layout = vec![descriptor.layout[0].clone(), self.layout.clone()];
+        descriptor.label = Some("particle_render_pipeline".into());
+        descriptor.vertex.shader = self.shader.clone();
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader = self.shader.clone();
+        }
+
+        Ok(descriptor)
+    }
+}
+
+type DrawParticles = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetParticleBindGroup<1>,
+    DrawParticleInstances,
+);
+
+struct SetParticleBindGroup<const I: usize>;
+
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetParticleBindGroup<I> {
+    type Param = SRes<ParticleRenderBuffers>;
+    type ViewQuery = ();
+    type ItemQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        _entity: Option<()>,
+        buffers: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        // Aucun état à dessiner pour l'instant (backend CPU avant sa première publication)
+        let Some(bind_group) = &buffers.into_inner().bind_group else {
+            return RenderCommandResult::Skip;
+        };
+
+        pass.set_bind_group(I, bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+/// Dessine le mesh une fois par particule de la simulation : les instances `offset..offset + count`
+/// sont les index globaux des particules dans les buffers de positions
+struct DrawParticleInstances;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawParticleInstances {
+    type Param = (SRes<RenderAssets<RenderMesh>>, SRes<MeshAllocator>);
+    type ViewQuery = ();
+    type ItemQuery = Read<ParticleInstances>;
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        particles: Option<&'w ParticleInstances>,
+        (meshes, mesh_allocator): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let mesh_allocator = mesh_allocator.into_inner();
+
+        let Some(particles) = particles else {
+            return RenderCommandResult::Skip;
+        };
+        let mesh_id = particles.mesh.id();
+        let Some(gpu_mesh) = meshes.into_inner().get(mesh_id) else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(vertex_slice) = mesh_allocator.mesh_vertex_slice(&mesh_id) else {
+            return RenderCommandResult::Skip;
+        };
+
+        let instances = particles.offset..particles.offset + particles.count;
+        pass.set_vertex_buffer(0, vertex_slice.buffer.slice(..));
+
+        match &gpu_mesh.buffer_info {
+            RenderMeshBufferInfo::Indexed { index_format, count } => {
+                let Some(index_slice) = mesh_allocator.mesh_index_slice(&mesh_id) else {
+                    return RenderCommandResult::Skip;
+                };
+
+                pass.set_index_buffer(index_slice.buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(
+                    index_slice.range.start..(index_slice.range.start + count),
+                    vertex_slice.range.start as i32,
+                    instances,
+                );
+            }
+            RenderMeshBufferInfo::NonIndexed => {
+                pass.draw(vertex_slice.range, instances);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
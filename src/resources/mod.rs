@@ -1,2 +1,5 @@
 pub mod particle_config;
-pub mod simulation_config;
\ No newline at end of file
+pub mod particle_snapshot;
+pub mod physics_params;
+pub mod simulation_config;
+pub mod simulation_rng;
\ No newline at end of file
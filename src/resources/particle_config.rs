@@ -1,38 +1,61 @@
 use bevy::prelude::*;
 use rand::Rng;
+use crate::genetics::genome::Genome;
 use crate::globals::*;
+use crate::resources::physics_params::TypeProperties;
+use crate::resources::simulation_rng::SimulationRngs;
 
 #[derive(Resource)]
 pub struct ParticleConfig {
     pub num_particles: u32,
     pub world_size: f32,
     pub num_types: u32,
+    pub num_simulations: u32,
     pub particle_size: f32,
-    pub force_matrix: Vec<f32>,
+    /// Règles de chaque simulation : matrice `num_types²` et propriétés des types
+    pub genomes: Vec<Genome>,
+    /// Plage (offset, nombre) des particules de chaque simulation dans les buffers GPU
+    pub simulation_ranges: Vec<[u32; 2]>,
     pub update_timer: Timer,
 }
 
 impl Default for ParticleConfig {
     fn default() -> Self {
         let num_types = DEFAULT_PARTICLE_TYPES as u32;
-        let mut config = Self {
+        let num_simulations = DEFAULT_SIMULATION_COUNT as u32;
+        // Les matrices sont tirées à l'entrée en simulation, avec la graine de la config
+        Self {
             num_particles: DEFAULT_PARTICLE_COUNT as u32,
             world_size: DEFAULT_WORLD_SIZE,
             num_types,
+            num_simulations,
             particle_size: DEFAULT_PARTICLE_SIZE,
-            force_matrix: vec![0.0; (num_types * num_types) as usize],
+            genomes: vec![Genome::new(num_types); num_simulations as usize],
+            simulation_ranges: Vec::new(),
             update_timer: Timer::from_seconds(PHYSICS_TIMESTEP, TimerMode::Repeating),
-        };
-
-        config.generate_random_forces();
-        config
+        }
     }
 }
 
 impl ParticleConfig {
-    /// Génère des forces d'interaction aléatoires entre les types
-    pub fn generate_random_forces(&mut self) {
-        let mut rng = rand::rng();
+    /// Redimensionne les génomes pour `num_simulations` simulations de `num_types` types
+    pub fn resize(&mut self, num_simulations: u32, num_types: u32) {
+        self.num_simulations = num_simulations;
+        self.num_types = num_types;
+        self.genomes = vec![Genome::new(num_types); num_simulations as usize];
+    }
+
+    /// Génère des forces d'interaction aléatoires pour chaque simulation, chacune avec son générateur
+    pub fn generate_random_forces(&mut self, rngs: &mut SimulationRngs) {
+        for sim in 0..self.num_simulations {
+            if let Some(rng) = rngs.get_mut(sim) {
+                self.generate_random_forces_for(sim, &mut rng.forces);
+            }
+        }
+    }
+
+    /// Génère des forces d'interaction aléatoires entre les types d'une simulation
+    pub fn generate_random_forces_for(&mut self, sim: u32, rng: &mut impl Rng) {
 
         // Remplit la matrice avec des valeurs équilibrées
         for i in 0..self.num_types {
@@ -45,57 +68,101 @@ impl ParticleConfig {
                     rng.random::<f32>() * 4.0 - 2.0 // Entre -2.0 et 2.0
                 };
 
-                let index = (i * self.num_types + j) as usize;
-                self.force_matrix[index] = force;
+                self.set_force(sim, i, j, force);
             }
         }
 
-        println!("Generated balanced force matrix:");
-        self.print_force_matrix();
+        println!("Generated balanced force matrix for simulation {}:", sim);
+        self.print_force_matrix(sim);
     }
 
     /// Définit la force entre deux types
-    pub fn set_force(&mut self, type_a: u32, type_b: u32, force: f32) {
-        let index = (type_a * self.num_types + type_b) as usize;
-        if index < self.force_matrix.len() {
-            self.force_matrix[index] = force;
+    pub fn set_force(&mut self, sim: u32, type_a: u32, type_b: u32, force: f32) {
+        if let Some(genome) = self.genomes.get_mut(sim as usize) {
+            genome.set_force(type_a, type_b, force);
         }
     }
 
     /// Récupère la force entre deux types
-    pub fn get_force(&self, type_a: u32, type_b: u32) -> f32 {
-        let index = (type_a * self.num_types + type_b) as usize;
-        self.force_matrix.get(index).copied().unwrap_or(0.0)
+    pub fn get_force(&self, sim: u32, type_a: u32, type_b: u32) -> f32 {
+        self.genomes.get(sim as usize).map_or(0.0, |genome| genome.force(type_a, type_b))
+    }
+
+    /// Matrices concaténées au format du buffer GPU, complétées jusqu'à `MAX_SIMULATIONS`
+    pub fn flattened_force_matrices(&self) -> Vec<f32> {
+        let matrix_size = (self.num_types * self.num_types) as usize;
+        let mut flattened: Vec<f32> = self.genomes.iter().flat_map(|genome| genome.force_matrix.iter().copied()).collect();
+        flattened.resize(MAX_SIMULATIONS * matrix_size, 0.0);
+        flattened
+    }
+
+    /// Propriétés d'un type, neutres s'il n'existe pas
+    pub fn get_type_properties(&self, sim: u32, particle_type: u32) -> TypeProperties {
+        self.genomes
+            .get(sim as usize)
+            .and_then(|genome| genome.type_properties.get(particle_type as usize))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_type_properties(&mut self, sim: u32, particle_type: u32, properties: TypeProperties) {
+        if let Some(value) = self.genomes.get_mut(sim as usize).and_then(|genome| genome.type_properties.get_mut(particle_type as usize)) {
+            *value = properties;
+        }
+    }
+
+    /// Alterne noyaux (types pairs) et électrons (types impairs) dans une simulation
+    pub fn set_nuclei_and_electrons(&mut self, sim: u32) {
+        for particle_type in 0..self.num_types {
+            let properties = if particle_type % 2 == 0 { TypeProperties::NUCLEUS } else { TypeProperties::ELECTRON };
+            self.set_type_properties(sim, particle_type, properties);
+        }
+    }
+
+    /// Propriétés concaténées au format du buffer GPU, complétées jusqu'à `MAX_SIMULATIONS`
+    pub fn flattened_type_properties(&self) -> Vec<TypeProperties> {
+        let mut flattened: Vec<TypeProperties> = self.genomes.iter().flat_map(|genome| genome.type_properties.iter().copied()).collect();
+        flattened.resize(MAX_SIMULATIONS * self.num_types as usize, TypeProperties::default());
+        flattened
+    }
+
+    /// Ranges au format du buffer GPU, complétées jusqu'à `MAX_SIMULATIONS`
+    pub fn padded_simulation_ranges(&self) -> Vec<[u32; 2]> {
+        let mut ranges = self.simulation_ranges.clone();
+        ranges.resize(MAX_SIMULATIONS, [0, 0]);
+        ranges
     }
 
     /// Affiche la matrice des forces
-    pub fn print_force_matrix(&self) {
+    pub fn print_force_matrix(&self, sim: u32) {
         for i in 0..self.num_types {
             for j in 0..self.num_types {
-                let force = self.get_force(i, j);
+                let force = self.get_force(sim, i, j);
                 print!("{:6.2} ", force);
             }
             println!();
         }
     }
 
-    /// Configure des forces intéressantes prédéfinies
-    pub fn set_interesting_forces(&mut self) {
-        self.force_matrix.fill(0.0);
+    /// Configure des forces intéressantes prédéfinies pour une simulation
+    pub fn set_interesting_forces(&mut self, sim: u32, rng: &mut impl Rng) {
+        if let Some(genome) = self.genomes.get_mut(sim as usize) {
+            genome.force_matrix.fill(0.0);
+        }
 
         match self.num_types {
             3 => {
                 // Configuration rock-paper-scissors
-                self.set_force(0, 1, 1.0);   // Rouge attire Vert
-                self.set_force(1, 2, 1.0);   // Vert attire Bleu
-                self.set_force(2, 0, 1.0);   // Bleu attire Rouge
-                self.set_force(1, 0, -0.5);  // Vert repousse Rouge
-                self.set_force(2, 1, -0.5);  // Bleu repousse Vert
-                self.set_force(0, 2, -0.5);  // Rouge repousse Bleu
+                self.set_force(sim, 0, 1, 1.0);   // Rouge attire Vert
+                self.set_force(sim, 1, 2, 1.0);   // Vert attire Bleu
+                self.set_force(sim, 2, 0, 1.0);   // Bleu attire Rouge
+                self.set_force(sim, 1, 0, -0.5);  // Vert repousse Rouge
+                self.set_force(sim, 2, 1, -0.5);  // Bleu repousse Vert
+                self.set_force(sim, 0, 2, -0.5);  // Rouge repousse Bleu
 
                 // Auto-répulsion légère
                 for i in 0..3 {
-                    self.set_force(i, i, -0.3);
+                    self.set_force(sim, i, i, -0.3);
                 }
             },
             6 => {
@@ -105,25 +172,25 @@ impl ParticleConfig {
                     let next = (i + 1) % 6;
                     let prev = (i + 5) % 6;
 
-                    self.set_force(i, next, 1.2);      // Attire le suivant
-                    self.set_force(i, prev, -0.8);     // Repousse le précédent
-                    self.set_force(i, i, -0.4);        // Auto-répulsion
+                    self.set_force(sim, i, next, 1.2);      // Attire le suivant
+                    self.set_force(sim, i, prev, -0.8);     // Repousse le précédent
+                    self.set_force(sim, i, i, -0.4);        // Auto-répulsion
 
                     // Forces moyennes avec les autres
                     for j in 0..6 {
                         if j != i && j != next && j != prev {
-                            self.set_force(i, j, (rand::rng().random::<f32>() - 0.5) * 0.6);
+                            self.set_force(sim, i, j, (rng.random::<f32>() - 0.5) * 0.6);
                         }
                     }
                 }
             },
             _ => {
                 // Configuration générique aléatoire
-                self.generate_random_forces();
+                self.generate_random_forces_for(sim, rng);
             }
         }
 
-        println!("Set interesting forces:");
-        self.print_force_matrix();
+        println!("Set interesting forces for simulation {}:", sim);
+        self.print_force_matrix(sim);
     }
-}
\ No newline at end of file
+}
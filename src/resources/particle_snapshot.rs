@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+/// Dernier état des particules publié par le backend actif, indexé par particule, simulation après simulation
+#[derive(Resource, Default, Clone)]
+pub struct ParticleSnapshot {
+    /// (x, y, z, type)
+    pub positions: Vec<[f32; 4]>,
+    /// (x, y, z, index de simulation)
+    pub velocities: Vec<[f32; 4]>,
+}
+
+/// Positions de l'avant-dernier état, pour interpoler le rendu entre les deux derniers états
+/// complets (voir `BackendSync::blend_factor`)
+#[derive(Resource, Default)]
+pub struct PreviousSnapshot {
+    pub positions: Vec<[f32; 4]>,
+}
@@ -0,0 +1,142 @@
+use std::ops::RangeInclusive;
+
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+use crate::globals::MAX_GRID_DIM;
+use crate::resources::simulation_config::{NeighborSearch, SimulationConfig};
+
+/// Paramètres physiques envoyés au compute shader (uniform `params`).
+/// Uniquement des scalaires 32 bits, dans l'ordre du struct WGSL, pour éviter tout padding.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct PhysicsParams {
+    pub force_model: u32,
+    pub beta: f32,
+    pub max_force_range: f32,
+    pub force_scale: f32,
+    pub friction: f32,
+    pub max_velocity: f32,
+    pub min_distance: f32,
+    pub boundary_mode: u32,
+    pub restitution: f32,
+    pub wall_stiffness: f32,
+    pub centering_strength: f32,
+    pub dimensions: u32,
+}
+
+impl From<&SimulationConfig> for PhysicsParams {
+    fn from(config: &SimulationConfig) -> Self {
+        Self {
+            force_model: config.force_model.shader_id(),
+            beta: config.force_beta,
+            max_force_range: config.max_force_range,
+            force_scale: config.force_scale,
+            friction: config.friction_per_step(config.step_dt()),
+            max_velocity: config.max_velocity,
+            min_distance: config.min_distance,
+            boundary_mode: config.boundary_mode.shader_id(),
+            restitution: config.restitution,
+            wall_stiffness: config.wall_stiffness,
+            centering_strength: config.centering_strength,
+            dimensions: config.dimension.shader_id(),
+        }
+    }
+}
+
+/// Propriétés physiques d'un type de particule (buffer `type_properties`), exprimées par rapport
+/// aux paramètres globaux : un type à 1 partout se comporte comme avant
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, Serialize, Deserialize)]
+pub struct TypeProperties {
+    /// Divise l'accélération : un type lourd réagit moins aux forces
+    pub mass: f32,
+    /// Portée des forces subies, en fraction de `max_force_range` ; au plus 1 pour que
+    /// les 27 cellules voisines de la grille suffisent toujours
+    pub radius: f32,
+    /// Multiplie `max_velocity`
+    pub max_speed: f32,
+    /// Exposant de la friction par pas : 0 sans amortissement, 2 freiné deux fois plus vite
+    pub damping: f32,
+}
+
+impl Default for TypeProperties {
+    fn default() -> Self {
+        Self {
+            mass: 1.0,
+            radius: 1.0,
+            max_speed: 1.0,
+            damping: 1.0,
+        }
+    }
+}
+
+impl TypeProperties {
+    pub const MASS_RANGE: RangeInclusive<f32> = 0.1..=10.0;
+    pub const RADIUS_RANGE: RangeInclusive<f32> = 0.1..=1.0;
+    pub const MAX_SPEED_RANGE: RangeInclusive<f32> = 0.1..=4.0;
+    pub const DAMPING_RANGE: RangeInclusive<f32> = 0.0..=4.0;
+
+    /// Type lourd et lent, à courte portée
+    pub const NUCLEUS: TypeProperties = TypeProperties {
+        mass: 4.0,
+        radius: 0.6,
+        max_speed: 0.5,
+        damping: 2.0,
+    };
+
+    /// Type léger et rapide, peu amorti
+    pub const ELECTRON: TypeProperties = TypeProperties {
+        mass: 0.25,
+        radius: 1.0,
+        max_speed: 2.0,
+        damping: 0.5,
+    };
+
+    /// Ramène chaque propriété dans ses bornes
+    pub fn clamped(self) -> Self {
+        let clamp = |value: f32, range: RangeInclusive<f32>| value.clamp(*range.start(), *range.end());
+        Self {
+            mass: clamp(self.mass, Self::MASS_RANGE),
+            radius: clamp(self.radius, Self::RADIUS_RANGE),
+            max_speed: clamp(self.max_speed, Self::MAX_SPEED_RANGE),
+            damping: clamp(self.damping, Self::DAMPING_RANGE),
+        }
+    }
+}
+
+/// Paramètres de la grille spatiale (uniform `grid_params`), partagés par les passes de binning
+/// et la passe de forces. Chaque simulation possède sa propre grille de `grid_dim³` cellules.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct GridParams {
+    pub grid_dim: u32,
+    pub num_cells: u32,
+    pub cell_size: f32,
+    pub half_world: f32,
+    pub enabled: u32,
+}
+
+impl GridParams {
+    /// Nombre de cellules à allouer pour `num_simulations` grilles de taille maximale
+    pub fn max_cells(num_simulations: u32) -> u32 {
+        num_simulations.max(1) * MAX_GRID_DIM * MAX_GRID_DIM * MAX_GRID_DIM
+    }
+}
+
+impl From<&SimulationConfig> for GridParams {
+    fn from(config: &SimulationConfig) -> Self {
+        // Des cellules au moins aussi larges que la portée : les 27 voisines suffisent
+        let grid_dim = ((config.world_size / config.max_force_range.max(f32::EPSILON)).floor() as u32)
+            .clamp(1, MAX_GRID_DIM);
+        let num_simulations = config.simulation_count.max(1) as u32;
+
+        Self {
+            grid_dim,
+            num_cells: num_simulations * grid_dim * grid_dim * grid_dim,
+            cell_size: config.world_size / grid_dim as f32,
+            half_world: config.world_size * 0.5,
+            enabled: (config.neighbor_search == NeighborSearch::SpatialHash) as u32,
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::globals::*;
 
-#[derive(Resource, Clone)]
+/// Configuration d'une session, choisie dans le menu ou chargée d'un fichier RON en mode headless
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulationConfig {
     // Paramètres de grille
     pub world_size: f32,
+    pub dimension: Dimension,
 
     // Paramètres de simulation
     pub simulation_count: usize,
@@ -12,9 +16,31 @@ pub struct SimulationConfig {
     pub particle_types: usize,
     pub particles_per_simulation: usize,
 
+    // Graine de tous les tirages aléatoires de la session
+    pub seed: u64,
+
+    // Moteur physique
+    pub backend: BackendKind,
+
+    // Paramètres temporels
+    pub time_scale: f32,
+    pub substeps: u32,
+
     // Paramètres des forces
+    pub force_model: ForceModel,
+    pub force_beta: f32,
     pub max_force_range: f32,
     pub velocity_half_life: f32,
+    pub force_scale: f32,
+    pub max_velocity: f32,
+    pub min_distance: f32,
+    pub neighbor_search: NeighborSearch,
+
+    // Paramètres des bords
+    pub boundary_mode: BoundaryMode,
+    pub restitution: f32,
+    pub wall_stiffness: f32,
+    pub centering_strength: f32,
 
     // Paramètres de nourriture
     pub food_count: usize,
@@ -23,34 +49,487 @@ pub struct SimulationConfig {
     pub food_value: f32,
 
     // Paramètres génétiques
+    pub epoch_duration: f32,
     pub elite_ratio: f32,
+    pub selection: SelectionMethod,
+    pub tournament_size: usize,
+    pub truncation_ratio: f32,
+    pub mutation: MutationMethod,
     pub mutation_rate: f32,
+    pub mutation_sigma: f32,
+    pub crossover: CrossoverMethod,
     pub crossover_rate: f32,
+    pub blend_alpha: f32,
+    pub fitness_weights: FitnessWeights,
 
     // Paramètres de viewport
     pub viewport_rows: u32,
     pub viewport_cols: u32,
+    /// Interpole le rendu entre les deux derniers états publiés
+    pub render_interpolation: bool,
 }
 
 impl Default for SimulationConfig {
     fn default() -> Self {
         Self {
             world_size: DEFAULT_WORLD_SIZE,
+            dimension: Dimension::default(),
             simulation_count: DEFAULT_SIMULATION_COUNT,
             particle_count: DEFAULT_PARTICLE_COUNT,
             particle_types: DEFAULT_PARTICLE_TYPES,
             particles_per_simulation: DEFAULT_PARTICLE_COUNT / DEFAULT_SIMULATION_COUNT,
+            seed: rand::random(),
+            backend: BackendKind::default(),
+            time_scale: DEFAULT_TIME_SCALE,
+            substeps: DEFAULT_SUBSTEPS,
+            force_model: ForceModel::default(),
+            force_beta: DEFAULT_FORCE_BETA,
             max_force_range: DEFAULT_MAX_FORCE_RANGE,
             velocity_half_life: VELOCITY_HALF_LIFE,
+            force_scale: DEFAULT_FORCE_SCALE,
+            max_velocity: DEFAULT_MAX_VELOCITY,
+            min_distance: DEFAULT_MIN_DISTANCE,
+            neighbor_search: NeighborSearch::default(),
+            boundary_mode: BoundaryMode::default(),
+            restitution: DEFAULT_RESTITUTION,
+            wall_stiffness: DEFAULT_WALL_STIFFNESS,
+            centering_strength: DEFAULT_CENTERING_STRENGTH,
             food_count: DEFAULT_FOOD_COUNT,
             food_respawn_enabled: true,
             food_respawn_time: DEFAULT_FOOD_RESPAWN_TIME,
             food_value: DEFAULT_FOOD_VALUE,
+            epoch_duration: DEFAULT_EPOCH_DURATION,
             elite_ratio: DEFAULT_ELITE_RATIO,
+            selection: SelectionMethod::default(),
+            tournament_size: DEFAULT_TOURNAMENT_SIZE,
+            truncation_ratio: DEFAULT_TRUNCATION_RATIO,
+            mutation: MutationMethod::default(),
             mutation_rate: DEFAULT_MUTATION_RATE,
+            mutation_sigma: DEFAULT_MUTATION_SIGMA,
+            crossover: CrossoverMethod::default(),
             crossover_rate: DEFAULT_CROSSOVER_RATE,
+            blend_alpha: DEFAULT_BLEND_ALPHA,
+            fitness_weights: FitnessWeights::default(),
             viewport_rows: 2,
             viewport_cols: 4,
+            render_interpolation: true,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Refuse les configurations que la simulation ne peut pas exécuter (division par zéro,
+    /// monde vide…), qu'elles viennent de la ligne de commande ou d'un fichier
+    pub fn validate(&self) -> Result<(), String> {
+        if self.particle_types == 0 {
+            return Err("Il faut au moins un type de particule".to_string());
+        }
+        if !self.world_size.is_finite() || self.world_size <= 0.0 {
+            return Err(format!("Taille du monde invalide : {} (doit être positive)", self.world_size));
+        }
+        if !(1..=MAX_SIMULATIONS).contains(&self.simulation_count) {
+            return Err(format!("Nombre de simulations invalide : {} (entre 1 et {})", self.simulation_count, MAX_SIMULATIONS));
+        }
+        if self.particle_count == 0 || self.particles_per_simulation == 0 {
+            return Err(format!(
+                "Pas assez de particules : {} pour {} simulations (au moins une par simulation)",
+                self.particle_count, self.simulation_count
+            ));
+        }
+        // Sans temps qui passe, aucune époque ne se termine
+        if !self.time_scale.is_finite() || self.time_scale <= 0.0 {
+            return Err(format!("Vitesse invalide : {} (doit être positive)", self.time_scale));
+        }
+        if self.substeps == 0 {
+            return Err("Il faut au moins un sous-pas".to_string());
+        }
+        if !self.max_force_range.is_finite() || self.max_force_range <= 0.0 {
+            return Err(format!("Portée des forces invalide : {} (doit être positive)", self.max_force_range));
+        }
+        if self.velocity_half_life.is_nan() || self.velocity_half_life <= 0.0 {
+            return Err(format!("Demi-vie de la vitesse invalide : {} (doit être positive)", self.velocity_half_life));
+        }
+        // Une durée infinie est permise : l'époque ne se termine jamais
+        if self.epoch_duration.is_nan() || self.epoch_duration <= 0.0 {
+            return Err(format!("Durée d'époque invalide : {} (doit être positive)", self.epoch_duration));
+        }
+        Ok(())
+    }
+
+    /// Vrai si un des paramètres physiques ou temporels diffère
+    pub fn physics_differs(&self, other: &SimulationConfig) -> bool {
+        self.time_scale != other.time_scale
+            || self.substeps != other.substeps
+            || self.force_model != other.force_model
+            || self.force_beta != other.force_beta
+            || self.max_force_range != other.max_force_range
+            || self.velocity_half_life != other.velocity_half_life
+            || self.force_scale != other.force_scale
+            || self.max_velocity != other.max_velocity
+            || self.min_distance != other.min_distance
+            || self.neighbor_search != other.neighbor_search
+            || self.boundary_mode != other.boundary_mode
+            || self.restitution != other.restitution
+            || self.wall_stiffness != other.wall_stiffness
+            || self.centering_strength != other.centering_strength
+    }
+
+    /// Vitesse la plus élevée pour `substeps` sous-pas : à 60 images/s, les `time_scale × substeps`
+    /// sous-pas d'une frame doivent tenir dans un lot
+    pub fn max_time_scale(substeps: u32) -> f32 {
+        (MAX_STEPS_PER_FRAME as f32 / substeps.max(1) as f32).min(MAX_TIME_SCALE)
+    }
+
+    /// Nombre de sous-pas le plus élevé à la vitesse `time_scale`, même contrainte
+    pub fn max_substeps(time_scale: f32) -> u32 {
+        ((MAX_STEPS_PER_FRAME as f32 / time_scale.max(MIN_TIME_SCALE)) as u32).clamp(1, MAX_SUBSTEPS)
+    }
+
+    /// Durée d'un sous-pas GPU : chaque pas de `PHYSICS_TIMESTEP` est découpé en `substeps`
+    pub fn step_dt(&self) -> f32 {
+        PHYSICS_TIMESTEP / self.substeps.max(1) as f32
+    }
+
+    /// Facteur multiplicatif appliqué à la vitesse à chaque pas de `dt` secondes :
+    /// la vitesse est divisée par deux toutes les `velocity_half_life` secondes,
+    /// quel que soit le nombre de pas.
+    pub fn friction_per_step(&self, dt: f32) -> f32 {
+        0.5f32.powf(dt / self.velocity_half_life.max(f32::EPSILON))
+    }
+}
+
+/// Moteur exécutant la physique
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BackendKind {
+    /// Compute shaders (voir `GpuBackend`)
+    #[default]
+    Gpu,
+    /// Réimplémentation Rust multithreadée du même noyau
+    Cpu,
+}
+
+impl BackendKind {
+    pub const ALL: [BackendKind; 2] = [BackendKind::Gpu, BackendKind::Cpu];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackendKind::Gpu => "GPU (compute shader)",
+            BackendKind::Cpu => "CPU (multithread)",
+        }
+    }
+}
+
+/// Nombre d'axes sur lesquels les particules se déplacent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Dimension {
+    /// Plan z = 0 : apparition, physique et nourriture, caméras orthographiques vues de dessus
+    Two,
+    #[default]
+    Three,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 2] = [Dimension::Two, Dimension::Three];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Dimension::Two => "2D",
+            Dimension::Three => "3D",
+        }
+    }
+
+    /// Identifiant passé au shader : nombre d'axes simulés
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            Dimension::Two => 2,
+            Dimension::Three => 3,
+        }
+    }
+
+    /// Point aléatoire du cube (ou du carré en 2D) de côté `world_size` centré sur l'origine
+    pub fn random_point(&self, rng: &mut impl rand::Rng, world_size: f32) -> Vec3 {
+        let mut coord = || rng.random::<f32>() * world_size - world_size * 0.5;
+        let (x, y) = (coord(), coord());
+        let z = match self {
+            Dimension::Two => 0.0,
+            Dimension::Three => coord(),
+        };
+        Vec3::new(x, y, z)
+    }
+}
+
+/// Loi d'interaction appliquée par le compute shader
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ForceModel {
+    /// Noyau classique de Particle Life : répulsion universelle sous `beta`, puis rampe triangulaire
+    #[default]
+    ParticleLife,
+    /// Loi en 1/r² avec coupure dure (modèle historique)
+    InverseSquare,
+}
+
+impl ForceModel {
+    pub const ALL: [ForceModel; 2] = [ForceModel::ParticleLife, ForceModel::InverseSquare];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ForceModel::ParticleLife => "Particle Life (β)",
+            ForceModel::InverseSquare => "Inverse carré",
+        }
+    }
+
+    /// Identifiant passé au shader (voir `FORCE_MODEL_*` dans particle_compute.wgsl)
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            ForceModel::ParticleLife => 0,
+            ForceModel::InverseSquare => 1,
+        }
+    }
+}
+
+/// Recherche des voisines dans la passe de forces
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NeighborSearch {
+    /// Grille uniforme de la taille de la portée : seules les 27 cellules voisines sont visitées
+    #[default]
+    SpatialHash,
+    /// Toutes les particules de la simulation (O(N²), référence)
+    BruteForce,
+}
+
+impl NeighborSearch {
+    pub const ALL: [NeighborSearch; 2] = [NeighborSearch::SpatialHash, NeighborSearch::BruteForce];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NeighborSearch::SpatialHash => "Grille spatiale",
+            NeighborSearch::BruteForce => "Force brute",
+        }
+    }
+}
+
+/// Comportement des particules aux bords du monde
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BoundaryMode {
+    /// Monde torique : les particules réapparaissent de l'autre côté, distances en image minimale
+    Wrap,
+    /// Murs rigides, la vitesse normale est inversée et multipliée par la restitution
+    #[default]
+    Reflect,
+    /// Potentiel répulsif dans une bande le long des murs
+    SoftWall,
+    /// Monde ouvert, avec un léger rappel vers le centre
+    Unbounded,
+}
+
+impl BoundaryMode {
+    pub const ALL: [BoundaryMode; 4] = [
+        BoundaryMode::Wrap,
+        BoundaryMode::Reflect,
+        BoundaryMode::SoftWall,
+        BoundaryMode::Unbounded,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BoundaryMode::Wrap => "Torique",
+            BoundaryMode::Reflect => "Murs rigides",
+            BoundaryMode::SoftWall => "Murs mous",
+            BoundaryMode::Unbounded => "Monde ouvert",
+        }
+    }
+
+    /// Identifiant passé au shader (voir `BOUNDARY_*` dans particle_compute.wgsl)
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            BoundaryMode::Wrap => 0,
+            BoundaryMode::Reflect => 1,
+            BoundaryMode::SoftWall => 2,
+            BoundaryMode::Unbounded => 3,
+        }
+    }
+}
+
+/// Choix des parents à chaque fin d'époque (voir `genetics::selection`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SelectionMethod {
+    /// Meilleure de `tournament_size` simulations tirées au hasard
+    #[default]
+    Tournament,
+    /// Probabilité proportionnelle au score
+    Roulette,
+    /// Probabilité proportionnelle au rang
+    Rank,
+    /// Tirage uniforme parmi la fraction `truncation_ratio` des meilleures
+    Truncation,
+}
+
+impl SelectionMethod {
+    pub const ALL: [SelectionMethod; 4] = [
+        SelectionMethod::Tournament,
+        SelectionMethod::Roulette,
+        SelectionMethod::Rank,
+        SelectionMethod::Truncation,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SelectionMethod::Tournament => "Tournoi",
+            SelectionMethod::Roulette => "Roulette",
+            SelectionMethod::Rank => "Rang",
+            SelectionMethod::Truncation => "Troncature",
+        }
+    }
+}
+
+/// Croisement des génomes de deux parents (voir `genetics::crossover`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CrossoverMethod {
+    /// Chaque case de la matrice vient de l'un ou l'autre parent
+    #[default]
+    Uniform,
+    /// Chaque ligne de la matrice vient entière de l'un ou l'autre parent
+    RowSwap,
+    /// Les cases suivant un point de coupure viennent du second parent
+    SinglePoint,
+    /// BLX-α : tirage dans l'intervalle des parents élargi de `blend_alpha`
+    Blend,
+}
+
+impl CrossoverMethod {
+    pub const ALL: [CrossoverMethod; 4] = [
+        CrossoverMethod::Uniform,
+        CrossoverMethod::RowSwap,
+        CrossoverMethod::SinglePoint,
+        CrossoverMethod::Blend,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CrossoverMethod::Uniform => "Uniforme",
+            CrossoverMethod::RowSwap => "Par ligne",
+            CrossoverMethod::SinglePoint => "Un point",
+            CrossoverMethod::Blend => "BLX-α",
+        }
+    }
+}
+
+/// Mutation de la matrice de forces (voir `genetics::mutation`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MutationMethod {
+    /// Bruit gaussien d'écart type `mutation_sigma`
+    #[default]
+    Gaussian,
+    /// Nouvelle valeur uniforme dans les bornes de la matrice
+    Reset,
+    /// Échange de deux lignes ou de deux colonnes
+    Swap,
+}
+
+impl MutationMethod {
+    pub const ALL: [MutationMethod; 3] = [
+        MutationMethod::Gaussian,
+        MutationMethod::Reset,
+        MutationMethod::Swap,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MutationMethod::Gaussian => "Gaussienne",
+            MutationMethod::Reset => "Réinitialisation",
+            MutationMethod::Swap => "Échange ligne/colonne",
+        }
+    }
+}
+
+/// Objectif mesuré sur une simulation pendant une époque (voir `genetics::fitness`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FitnessObjective {
+    /// Nourriture mangée
+    Food,
+    /// Énergie cinétique moyenne d'une particule
+    KineticEnergy,
+    /// Nombre moyen d'amas denses
+    Clusters,
+    /// Distance moyenne au centre de masse
+    Spread,
+    /// Chemin parcouru par le centre de masse
+    Travel,
+}
+
+impl FitnessObjective {
+    pub const ALL: [FitnessObjective; 5] = [
+        FitnessObjective::Food,
+        FitnessObjective::KineticEnergy,
+        FitnessObjective::Clusters,
+        FitnessObjective::Spread,
+        FitnessObjective::Travel,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FitnessObjective::Food => "Nourriture",
+            FitnessObjective::KineticEnergy => "Énergie cinétique",
+            FitnessObjective::Clusters => "Nombre d'amas",
+            FitnessObjective::Spread => "Étalement",
+            FitnessObjective::Travel => "Déplacement",
+        }
+    }
+
+    /// Nom de l'objectif dans les options de la ligne de commande
+    pub fn key(&self) -> &'static str {
+        match self {
+            FitnessObjective::Food => "food",
+            FitnessObjective::KineticEnergy => "energy",
+            FitnessObjective::Clusters => "clusters",
+            FitnessObjective::Spread => "spread",
+            FitnessObjective::Travel => "travel",
+        }
+    }
+}
+
+/// Poids de chaque objectif dans le score d'une simulation ; un poids nul désactive
+/// l'objectif, un poids négatif le pénalise
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FitnessWeights {
+    pub food: f32,
+    pub kinetic_energy: f32,
+    pub clusters: f32,
+    pub spread: f32,
+    pub travel: f32,
+}
+
+impl Default for FitnessWeights {
+    fn default() -> Self {
+        Self {
+            food: 1.0,
+            kinetic_energy: 0.0,
+            clusters: 0.0,
+            spread: 0.0,
+            travel: 0.0,
+        }
+    }
+}
+
+impl FitnessWeights {
+    pub fn weight(&self, objective: FitnessObjective) -> f32 {
+        match objective {
+            FitnessObjective::Food => self.food,
+            FitnessObjective::KineticEnergy => self.kinetic_energy,
+            FitnessObjective::Clusters => self.clusters,
+            FitnessObjective::Spread => self.spread,
+            FitnessObjective::Travel => self.travel,
+        }
+    }
+
+    pub fn weight_mut(&mut self, objective: FitnessObjective) -> &mut f32 {
+        match objective {
+            FitnessObjective::Food => &mut self.food,
+            FitnessObjective::KineticEnergy => &mut self.kinetic_energy,
+            FitnessObjective::Clusters => &mut self.clusters,
+            FitnessObjective::Spread => &mut self.spread,
+            FitnessObjective::Travel => &mut self.travel,
         }
     }
 }
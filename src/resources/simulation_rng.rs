@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Générateurs aléatoires d'une simulation, un par usage : régénérer une matrice
+/// ne décale pas la suite des apparitions de nourriture, et inversement
+pub struct SimulationRng {
+    pub spawn: StdRng,
+    pub forces: StdRng,
+    pub food: StdRng,
+    /// Reproduction de la simulation à chaque fin d'époque
+    pub genetics: StdRng,
+}
+
+/// Générateurs de toutes les simulations, dérivés de `SimulationConfig::seed`
+#[derive(Resource, Default)]
+pub struct SimulationRngs {
+    simulations: Vec<SimulationRng>,
+}
+
+impl SimulationRngs {
+    pub fn new(seed: u64, num_simulations: u32) -> Self {
+        let simulations = (0..num_simulations)
+            .map(|sim| SimulationRng {
+                spawn: StdRng::seed_from_u64(derive_seed(seed, sim, 0)),
+                forces: StdRng::seed_from_u64(derive_seed(seed, sim, 1)),
+                food: StdRng::seed_from_u64(derive_seed(seed, sim, 2)),
+                genetics: StdRng::seed_from_u64(derive_seed(seed, sim, 3)),
+            })
+            .collect();
+
+        Self { simulations }
+    }
+
+    pub fn get_mut(&mut self, sim: u32) -> Option<&mut SimulationRng> {
+        self.simulations.get_mut(sim as usize)
+    }
+}
+
+/// Graine d'un flux (`stream`) d'une simulation, indépendante des autres flux
+pub fn derive_seed(seed: u64, sim: u32, stream: u32) -> u64 {
+    splitmix64(seed ^ splitmix64(((sim as u64) << 32) | stream as u64))
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
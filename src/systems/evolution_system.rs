@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::simulation::*;
+use crate::genetics::{crossover::crossover_operator, fitness::*, mutation::{mutate_type_properties, mutation_operator}, selection::selection_strategy};
+use crate::globals::*;
+use crate::resources::{particle_config::*, particle_snapshot::*, simulation_config::*, simulation_rng::*};
+use crate::systems::simulation_system::{BackendSync, ParticleSpawner, SimulationClock};
+
+/// Génération en cours de l'algorithme génétique, remise à zéro à chaque sortie de simulation
+#[derive(Resource, Default)]
+pub struct Generation {
+    pub number: u32,
+    /// Pas simulés au début de la génération
+    pub started_at_step: u64,
+}
+
+impl Generation {
+    /// Temps simulé restant avant la fin de l'époque, en secondes
+    pub fn remaining(&self, clock: &SimulationClock, epoch_duration: f32) -> f32 {
+        let elapsed = clock.total_steps.saturating_sub(self.started_at_step) as f32 * PHYSICS_TIMESTEP;
+        (epoch_duration - elapsed).max(0.0)
+    }
+}
+
+/// Fin d'une époque, avec les scores obtenus par chaque simulation
+#[derive(Event)]
+pub struct EpochEnded {
+    pub generation: u32,
+    /// (simulation, score)
+    pub scores: Vec<(u32, f32)>,
+}
+
+/// Objectifs suivis pour chaque simulation pendant l'époque en cours
+#[derive(Resource, Default)]
+pub struct FitnessTracker {
+    /// Par simulation : (poids, objectif) ; reconstruit au premier état publié de l'époque
+    pub simulations: Vec<Vec<(f32, Box<dyn FitnessFunction>)>>,
+}
+
+/// Présente chaque nouvel état publié aux objectifs de sa simulation, dont le score devient
+/// la somme pondérée des objectifs
+pub fn evaluate_fitness(
+    mut tracker: ResMut<FitnessTracker>,
+    mut simulations: Query<&mut Simulation>,
+    snapshot: Res<ParticleSnapshot>,
+    particle_config: Res<ParticleConfig>,
+    sim_config: Res<SimulationConfig>,
+) {
+    if !snapshot.is_changed() || snapshot.positions.is_empty() {
+        return;
+    }
+
+    let ranges = &particle_config.simulation_ranges;
+    if tracker.simulations.len() != ranges.len() {
+        tracker.simulations = ranges.iter().map(|_| fitness_functions(&sim_config)).collect();
+    }
+
+    for mut simulation in simulations.iter_mut() {
+        let sim = simulation.id as usize;
+        let (Some(&[offset, count]), Some(functions), Some(genome)) =
+            (ranges.get(sim), tracker.simulations.get_mut(sim), particle_config.genomes.get(sim)) else {
+            continue;
+        };
+        let range = offset as usize..(offset + count) as usize;
+        let (Some(positions), Some(velocities)) = (snapshot.positions.get(range.clone()), snapshot.velocities.get(range)) else {
+            continue;
+        };
+
+        let sample = FitnessSample {
+            positions,
+            velocities,
+            type_properties: &genome.type_properties,
+            food_eaten: std::mem::take(&mut simulation.food_eaten),
+        };
+        for (_, function) in functions.iter_mut() {
+            function.observe(&sample);
+        }
+        simulation.score = functions.iter().map(|(weight, function)| weight * function.score()).sum();
+    }
+}
+
+/// Clôt l'époque écoulée : les scores sont relevés puis remis à zéro
+pub fn end_epoch(
+    mut generation: ResMut<Generation>,
+    mut simulations: Query<&mut Simulation>,
+    mut epoch_ended: EventWriter<EpochEnded>,
+    clock: Res<SimulationClock>,
+    backend_sync: Res<BackendSync>,
+    sim_config: Res<SimulationConfig>,
+) {
+    // Attend que tous les lots soient publiés : aucun état de l'ancienne génération ne doit
+    // arriver après la relance
+    if !backend_sync.initialized || backend_sync.in_flight > 0 {
+        return;
+    }
+    if generation.remaining(&clock, sim_config.epoch_duration) > 0.0 {
+        return;
+    }
+
+    let scores: Vec<(u32, f32)> = simulations
+        .iter_mut()
+        .map(|mut simulation| (simulation.id, std::mem::take(&mut simulation.score)))
+        .collect();
+
+    let best = scores.iter().map(|&(_, score)| score).max_by(f32::total_cmp).unwrap_or(0.0);
+    println!("🧬 Génération {} terminée, meilleur score {:.0}", generation.number + 1, best);
+
+    epoch_ended.write(EpochEnded { generation: generation.number, scores });
+    generation.number += 1;
+    generation.started_at_step = clock.total_steps;
+}
+
+/// Les simulations les mieux classées gardent leur génome ; les autres le remplacent par
+/// celui d'un descendant de deux parents choisis par la stratégie de sélection
+pub fn breed_generation(
+    mut epoch_ended: EventReader<EpochEnded>,
+    mut particle_config: ResMut<ParticleConfig>,
+    mut rngs: ResMut<SimulationRngs>,
+    sim_config: Res<SimulationConfig>,
+) {
+    for ended in epoch_ended.read() {
+        let mut ranking = ended.scores.clone();
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+        if ranking.is_empty() {
+            continue;
+        }
+
+        let elite_count = ((ranking.len() as f32 * sim_config.elite_ratio).round() as usize).clamp(1, ranking.len());
+        // Les parents sont lus dans la génération évaluée, pas dans les descendants déjà créés
+        let parents = particle_config.genomes.clone();
+        let selection = selection_strategy(&sim_config);
+        let crossover = crossover_operator(&sim_config);
+        let mutation = mutation_operator(&sim_config);
+
+        for &(sim, _) in &ranking[elite_count..] {
+            let Some(rng) = rngs.get_mut(sim) else {
+                continue;
+            };
+            let rng = &mut rng.genetics;
+
+            let parent_a = &parents[selection.select(&ranking, rng) as usize];
+            let parent_b = &parents[selection.select(&ranking, rng) as usize];
+
+            let mut child = parent_a.clone();
+            if rng.random::<f32>() < sim_config.crossover_rate {
+                crossover.cross(&mut child, parent_b, rng);
+            }
+            mutation.mutate(&mut child, sim_config.mutation_rate, rng);
+            mutate_type_properties(&mut child, sim_config.mutation_rate, rng);
+            child.clamp();
+
+            particle_config.genomes[sim as usize] = child;
+        }
+
+        let elites: Vec<String> = ranking[..elite_count].iter().map(|(sim, _)| format!("#{}", sim + 1)).collect();
+        println!("🧬 Élites conservées : {}", elites.join(", "));
+    }
+}
+
+/// Relance la population : `upload_particles` tire de nouvelles positions, et l'état publié
+/// de l'ancienne génération est oublié
+pub fn restart_generation(
+    mut epoch_ended: EventReader<EpochEnded>,
+    mut backend_sync: ResMut<BackendSync>,
+    mut spawner: ResMut<ParticleSpawner>,
+    mut snapshot: ResMut<ParticleSnapshot>,
+) {
+    if epoch_ended.read().count() == 0 {
+        return;
+    }
+
+    backend_sync.initialized = false;
+    *spawner = ParticleSpawner::default();
+    *snapshot = ParticleSnapshot::default();
+}
+
+/// Nouvelle génération : les objectifs repartent de zéro
+pub fn reset_fitness(
+    mut epoch_ended: EventReader<EpochEnded>,
+    mut tracker: ResMut<FitnessTracker>,
+    mut simulations: Query<&mut Simulation>,
+) {
+    if epoch_ended.read().count() == 0 {
+        return;
+    }
+
+    tracker.simulations.clear();
+    for mut simulation in simulations.iter_mut() {
+        simulation.food_eaten = 0.0;
+    }
+}
+
+/// Nettoyage à la sortie de l'état Simulation
+pub fn reset_generation(mut generation: ResMut<Generation>, mut tracker: ResMut<FitnessTracker>) {
+    *generation = Generation::default();
+    tracker.simulations.clear();
+}
@@ -1,57 +1,133 @@
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
-use rand::Rng;
 
-use crate::components::{particle::*, food::*, simulation::*};
-use crate::resources::simulation_config::*;
+use crate::components::{food::*, simulation::*};
+use crate::globals::FOOD_RADIUS;
+use crate::resources::{particle_config::*, particle_snapshot::*, simulation_config::*, simulation_rng::*};
+use crate::systems::evolution_system::EpochEnded;
 
+/// Ressources lues par `handle_food_interactions`
+#[derive(SystemParam)]
+pub struct FoodWorld<'w> {
+    time: Res<'w, Time>,
+    sim_config: Res<'w, SimulationConfig>,
+    particle_config: Res<'w, ParticleConfig>,
+    snapshot: Res<'w, ParticleSnapshot>,
+}
+
+/// Collisions nourriture / particules, sur le même état publié que le rendu (`ParticleSnapshot`).
+/// La nourriture visible est rangée dans une grille de cellules de `FOOD_RADIUS` : chaque
+/// particule ne consulte que sa cellule, en un seul passage sur la population.
 pub fn handle_food_interactions(
     mut commands: Commands,
-    time: Res<Time>,
-    sim_config: Res<SimulationConfig>,
+    world: FoodWorld,
+    mut rngs: ResMut<SimulationRngs>,
     mut simulations: Query<&mut Simulation>,
-    particles: Query<(&Transform, &ChildOf), With<LifeParticle>>,
-    mut food_query: Query<(Entity, &mut Transform, &Food, &mut FoodRespawnTimer, &mut Visibility, &ChildOf), (With<Food>, Without<LifeParticle>)>,
+    mut food_query: Query<(Entity, &mut Transform, &Food, &mut FoodRespawnTimer, &mut Visibility, &ChildOf), With<Food>>,
 ) {
-    let mut rng = rand::rng();
+    // Après une relance, l'état de l'ancienne génération est effacé : rien à faire avant le
+    // premier état de la nouvelle population
+    if world.snapshot.positions.len() != world.particle_config.num_particles as usize {
+        return;
+    }
+
+    // Nourriture visible, inscrite dans sa cellule et les 26 voisines
+    let mut food_cells: HashMap<(u32, IVec3), Vec<Entity>> = HashMap::new();
 
     // Traiter chaque nourriture
-    for (food_entity, mut food_transform, food, mut respawn_timer, mut visibility, parent) in food_query.iter_mut() {
+    for (food_entity, mut food_transform, food, mut respawn_timer, mut visibility, _) in food_query.iter_mut() {
         // Gérer le respawn timer
         if !matches!(*visibility, Visibility::Visible) {
-            respawn_timer.timer.tick(time.delta());
+            respawn_timer.timer.tick(world.time.delta());
             if respawn_timer.timer.just_finished() {
-                // Respawn la nourriture à une nouvelle position
-                let x = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
-                let y = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
-                let z = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
-                food_transform.translation = Vec3::new(x, y, z);
+                let Some(rng) = rngs.get_mut(food.simulation_id) else {
+                    continue;
+                };
+
+                // Respawn la nourriture à une nouvelle position, tirée du générateur de sa simulation
+                food_transform.translation = world.sim_config.dimension.random_point(&mut rng.food, world.sim_config.world_size);
                 *visibility = Visibility::Visible;
                 respawn_timer.timer.reset();
             }
             continue;
         }
 
-        // Vérifier collisions avec particules de la même simulation
-        for (particle_transform, particle_parent) in particles.iter() {
-            if particle_parent.parent() != parent.parent() {
-                continue; // Pas la même simulation
+        let home = collision_cell(food_transform.translation);
+        for z in -1..=1 {
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    food_cells.entry((food.simulation_id, home + IVec3::new(x, y, z)))
+                        .or_default()
+                        .push(food_entity);
+                }
             }
+        }
+    }
+
+    if food_cells.is_empty() {
+        return;
+    }
+
+    // Vérifier collisions avec particules de la même simulation
+    for (sim_id, &[offset, count]) in world.particle_config.simulation_ranges.iter().enumerate() {
+        let Some(particles) = world.snapshot.positions.get(offset as usize..(offset + count) as usize) else {
+            continue; // Aucun état publié pour l'instant
+        };
+
+        for position in particles {
+            let position = Vec3::new(position[0], position[1], position[2]);
+            let Some(candidates) = food_cells.get(&(sim_id as u32, collision_cell(position))) else {
+                continue;
+            };
 
-            let distance = food_transform.translation.distance(particle_transform.translation);
-            if distance < 1.0 { // Distance de collision
-                // Collision ! Ajouter score et cacher nourriture
+            for &food_entity in candidates {
+                let Ok((_, food_transform, food, mut respawn_timer, mut visibility, parent)) = food_query.get_mut(food_entity) else {
+                    continue;
+                };
+                if !matches!(*visibility, Visibility::Visible) || food_transform.translation.distance(position) >= FOOD_RADIUS {
+                    continue;
+                }
+
+                // Collision ! Compter la nourriture mangée et la cacher
                 if let Ok(mut simulation) = simulations.get_mut(parent.parent()) {
-                    simulation.add_score(food.value);
+                    simulation.eat(food.value);
                 }
 
                 *visibility = Visibility::Hidden;
                 respawn_timer.timer.reset();
-                break;
             }
         }
     }
 }
 
+/// Nouvelle génération : toute la nourriture réapparaît, tirée du générateur de sa simulation
+pub fn respawn_food_on_epoch(
+    mut epoch_ended: EventReader<EpochEnded>,
+    mut rngs: ResMut<SimulationRngs>,
+    sim_config: Res<SimulationConfig>,
+    mut food_query: Query<(&Food, &mut Transform, &mut FoodRespawnTimer, &mut Visibility)>,
+) {
+    if epoch_ended.read().count() == 0 {
+        return;
+    }
+
+    for (food, mut food_transform, mut respawn_timer, mut visibility) in food_query.iter_mut() {
+        let Some(rng) = rngs.get_mut(food.simulation_id) else {
+            continue;
+        };
+
+        food_transform.translation = sim_config.dimension.random_point(&mut rng.food, sim_config.world_size);
+        *visibility = Visibility::Visible;
+        respawn_timer.timer.reset();
+    }
+}
+
+/// Cellule de la grille de collision, de la taille de la distance de collision
+fn collision_cell(position: Vec3) -> IVec3 {
+    (position / FOOD_RADIUS).floor().as_ivec3()
+}
+
 pub fn display_scores(
     simulations: Query<&Simulation>,
     mut timer: Local<Timer>,
@@ -1,3 +1,4 @@
 pub mod simulation_system;
 pub mod food_system;
+pub mod evolution_system;
 pub mod viewport_system;
\ No newline at end of file
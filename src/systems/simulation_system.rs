@@ -1,82 +1,370 @@
 use bevy::prelude::*;
 use bevy::render::view::{Layer, RenderLayers};
-use bevy_app_compute::prelude::*;
-use rand::Rng;
 
 use crate::components::{particle::*, food::*, simulation::*};
-use crate::plugins::particle_life_plugin::ParticleComputeWorker;
-use crate::resources::{particle_config::*, simulation_config::*};
+use crate::globals::*;
+use crate::physics::{backend::SimulationBackend, grid::compare_with_brute_force, kernel::ForceField};
+use crate::resources::{particle_config::*, particle_snapshot::*, physics_params::*, simulation_config::*, simulation_rng::*};
 use crate::states::app_state::AppState;
+use crate::systems::evolution_system::Generation;
 
-pub fn initialize_gpu_data(
-    mut compute_worker: ResMut<AppComputeWorker<ParticleComputeWorker>>,
-    particles: Query<(&Transform, &LifeParticle)>,
-    mut next_state: ResMut<NextState<AppState>>,
-    mut initialized: Local<bool>,
+/// État de l'upload initial vers le backend, remis à zéro à chaque sortie de simulation
+#[derive(Resource, Default)]
+pub struct BackendSync {
+    pub initialized: bool,
+    /// Lots demandés dont l'état n'est pas encore publié : au-delà de ce que le backend
+    /// accepte, le temps s'accumule en attendant
+    pub in_flight: u32,
+    /// Instant du dernier lot de pas demandé (temps réel, en secondes)
+    pub dispatched_at: f64,
+    /// Écart entre les deux derniers lots, nul tant qu'un seul lot est parti
+    pub dispatch_interval: f64,
+}
+
+impl BackendSync {
+    /// Avancement du rendu entre l'état d'avant le dernier lot (0) et celui qu'il produit (1) :
+    /// le rendu a un lot de retard
+    pub fn blend_factor(&self, now: f64) -> f32 {
+        if self.dispatch_interval <= 0.0 {
+            return 1.0;
+        }
+        ((now - self.dispatched_at) / self.dispatch_interval).clamp(0.0, 1.0) as f32
+    }
+}
+
+/// Population en cours de création, tirée par morceaux de `SPAWN_CHUNK_SIZE` pour ne pas
+/// bloquer une frame entière sur les grandes populations
+#[derive(Resource, Default)]
+pub struct ParticleSpawner {
+    pub positions: Vec<[f32; 4]>,
+    pub velocities: Vec<[f32; 4]>,
+}
+
+/// Accumulateur du pas fixe, remis à zéro à chaque sortie de simulation
+#[derive(Resource, Default)]
+pub struct SimulationClock {
+    /// Temps simulé pas encore consommé, en secondes
+    pub accumulator: f32,
+    pub total_steps: u64,
+    /// Pas abandonnés faute de créneaux (frame trop lente ou vitesse trop élevée)
+    pub dropped_steps: u64,
+    /// Vrai tant que les derniers lots abandonnent des pas
+    pub dropping: bool,
+}
+
+/// Systèmes du backend actif, exécutés dans l'ordre : upload, synchronisation, pas, publication
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimulationStepSet;
+
+/// Condition d'exécution des systèmes propres à un backend
+pub fn backend_is(kind: BackendKind) -> impl Fn(Option<Res<SimulationConfig>>) -> bool + Clone {
+    move |sim_config: Option<Res<SimulationConfig>>| {
+        sim_config.is_some_and(|config| config.backend == kind)
+    }
+}
+
+/// Enregistre les systèmes génériques pour le backend `B`, actifs lorsque `kind` est sélectionné
+pub fn add_backend_systems<B: SimulationBackend>(app: &mut App, kind: BackendKind) {
+    app.add_systems(Update, (
+        upload_particles::<B>,
+        sync_force_matrices::<B>,
+        sync_type_properties::<B>,
+        sync_physics_params::<B>,
+        step_simulation::<B>,
+        publish_snapshot::<B>,
+    )
+        .chain()
+        .in_set(SimulationStepSet)
+        .run_if(in_state(AppState::Simulation))
+        .run_if(backend_is(kind)));
+}
+
+/// Tire les positions initiales, un morceau par frame, puis envoie la population complète au
+/// backend. Les particules ne sont pas des entités : elles n'existent que dans ses buffers.
+pub fn upload_particles<B: SimulationBackend>(
+    mut backend: ResMut<B>,
+    mut backend_sync: ResMut<BackendSync>,
+    mut spawner: ResMut<ParticleSpawner>,
+    mut rngs: ResMut<SimulationRngs>,
+    mut previous: ResMut<PreviousSnapshot>,
     config: Res<ParticleConfig>,
+    sim_config: Res<SimulationConfig>,
 ) {
-    if *initialized || !compute_worker.ready() {
+    if backend_sync.initialized {
         return;
     }
 
-    let particle_count = particles.iter().count();
-    println!("Found {} particles, expected {}", particle_count, config.num_particles);
+    let num_particles = config.num_particles as usize;
+    let spawned = spawner.positions.len();
+    let end = (spawned + SPAWN_CHUNK_SIZE).min(num_particles);
+
+    // Les buffers sont indexés par particule, simulation après simulation
+    for (sim_id, &[offset, count]) in config.simulation_ranges.iter().enumerate() {
+        let range = (offset as usize).max(spawned)..((offset + count) as usize).min(end);
+        if range.is_empty() {
+            continue;
+        }
+        let Some(rng) = rngs.get_mut(sim_id as u32) else {
+            continue;
+        };
 
-    let mut positions = Vec::new();
-    let mut velocities = Vec::new();
+        for index in range {
+            let particle_type = index % sim_config.particle_types;
+            let position = sim_config.dimension.random_point(&mut rng.spawn, sim_config.world_size);
+            spawner.positions.push([position.x, position.y, position.z, particle_type as f32]);
+            // La composante w de la vélocité porte l'index de la simulation
+            spawner.velocities.push([0.0, 0.0, 0.0, sim_id as f32]);
+        }
+    }
 
-    for (transform, particle) in particles.iter().take(config.num_particles as usize) {
-        let pos = transform.translation;
-        positions.push([pos.x, pos.y, pos.z, particle.particle_type as f32]);
-        velocities.push([0.0, 0.0, 0.0, 0.0]);
+    if spawner.positions.len() < num_particles {
+        debug!("{}/{} particules créées", spawner.positions.len(), num_particles);
+        return;
     }
 
+    let ParticleSpawner { positions, velocities } = std::mem::take(&mut *spawner);
     if !positions.is_empty() {
-        compute_worker.write_slice("positions", &positions);
-        compute_worker.write_slice("velocities", &velocities);
-        println!("✅ GPU initialized with {} particles", positions.len());
+        backend.upload_particles(&positions, &velocities, &config.padded_simulation_ranges());
+        println!("✅ Backend initialized with {} particles", positions.len());
+    }
+
+    // Le rendu ne doit pas interpoler depuis les positions de la génération précédente
+    previous.positions.clear();
+    backend_sync.dispatched_at = 0.0;
+    backend_sync.dispatch_interval = 0.0;
+    backend_sync.initialized = true;
+}
+
+/// Recopie les matrices de `ParticleConfig` dans le backend dès qu'elles changent
+pub fn sync_force_matrices<B: SimulationBackend>(
+    mut backend: ResMut<B>,
+    config: Res<ParticleConfig>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    backend.set_force_matrices(&config.flattened_force_matrices());
+}
+
+/// Recopie les propriétés des types de `ParticleConfig` dans le backend dès qu'elles changent
+pub fn sync_type_properties<B: SimulationBackend>(
+    mut backend: ResMut<B>,
+    config: Res<ParticleConfig>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+
+    backend.set_type_properties(&config.flattened_type_properties());
+}
+
+/// Recopie les paramètres physiques dans le backend dès que la config change
+pub fn sync_physics_params<B: SimulationBackend>(
+    mut backend: ResMut<B>,
+    sim_config: Res<SimulationConfig>,
+) {
+    if !sim_config.is_changed() {
+        return;
+    }
+
+    backend.set_physics(&sim_config);
+}
+
+/// Accumulateur à pas fixe : le temps écoulé (multiplié par `time_scale`) est converti en pas
+/// de `PHYSICS_TIMESTEP`, demandés au backend en un seul lot
+pub fn step_simulation<B: SimulationBackend>(
+    mut backend: ResMut<B>,
+    mut clock: ResMut<SimulationClock>,
+    mut backend_sync: ResMut<BackendSync>,
+    generation: Res<Generation>,
+    sim_config: Res<SimulationConfig>,
+    time: Res<Time>,
+    real_time: Res<Time<Real>>,
+) {
+    if !backend_sync.initialized {
+        return;
+    }
+
+    // Aucun pas au-delà de la fin de l'époque : les lots en vol se vident et `end_epoch` peut
+    // relancer la génération. Le temps écoulé entre-temps n'est pas reporté.
+    let owed = (generation.remaining(&clock, sim_config.epoch_duration) / PHYSICS_TIMESTEP).ceil() as u32;
+    if owed == 0 {
+        clock.accumulator = 0.0;
+        return;
+    }
+
+    clock.accumulator += time.delta_secs() * sim_config.time_scale;
+
+    // Lecture asynchrone : les pas suivants partent une fois un état précédent publié
+    if backend_sync.in_flight >= backend.max_batches_in_flight() {
+        return;
+    }
+    let pending = (clock.accumulator / PHYSICS_TIMESTEP).floor() as u32;
+    clock.accumulator -= pending as f32 * PHYSICS_TIMESTEP;
+    let pending = pending.min(owed);
+
+    // Un lot tient au plus `MAX_STEPS_PER_FRAME` sous-pas : le surplus est reporté au lot suivant,
+    // dans la limite d'un lot, et abandonné au-delà (sur les deux backends, pour qu'ils avancent
+    // au même rythme)
+    let substeps = sim_config.substeps.clamp(1, MAX_STEPS_PER_FRAME);
+    let batch = MAX_STEPS_PER_FRAME / substeps;
+    let steps = pending.min(batch);
+    let carried = (pending - steps).min(batch);
+    let dropped = pending - steps - carried;
+    clock.accumulator += carried as f32 * PHYSICS_TIMESTEP;
+    clock.dropped_steps += dropped as u64;
+
+    if dropped > 0 && !clock.dropping {
+        println!("⚠️ {} pas abandonnés : la simulation ne suit pas (vitesse {}×, {} sous-pas)",
+                 dropped, sim_config.time_scale, substeps);
+    }
+    clock.dropping = dropped > 0;
+
+    if steps == 0 {
+        return;
     }
 
-    *initialized = true;
+    clock.total_steps += steps as u64;
+    backend.step(steps, substeps);
+    backend_sync.in_flight += 1;
+
+    let now = real_time.elapsed_secs_f64();
+    backend_sync.dispatch_interval = if backend_sync.dispatched_at > 0.0 { now - backend_sync.dispatched_at } else { 0.0 };
+    backend_sync.dispatched_at = now;
+}
+
+/// Publie le dernier état complet du backend pour le rendu et la nourriture. Les positions
+/// remplacées sont conservées pour l'interpolation du rendu.
+pub fn publish_snapshot<B: SimulationBackend>(
+    mut backend: ResMut<B>,
+    mut backend_sync: ResMut<BackendSync>,
+    mut snapshot: ResMut<ParticleSnapshot>,
+    mut previous: ResMut<PreviousSnapshot>,
+) {
+    let Some(latest) = backend.poll_snapshot() else {
+        return;
+    };
+    backend_sync.in_flight = backend_sync.in_flight.saturating_sub(1);
+
+    // État calculé avant la relance de la population : jamais publié
+    if !backend_sync.initialized {
+        return;
+    }
+
+    let replaced = std::mem::replace(&mut *snapshot, latest);
+    previous.positions = replaced.positions;
 }
 
+/// F3 : vérifie sur CPU, à partir du dernier état publié, que la recherche par grille
+/// donne les mêmes forces que la boucle complète
+pub fn check_spatial_grid(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    snapshot: Res<ParticleSnapshot>,
+    config: Res<ParticleConfig>,
+    sim_config: Res<SimulationConfig>,
+) {
+    if !keyboard.just_pressed(KeyCode::F3) || snapshot.positions.is_empty() {
+        return;
+    }
+
+    let params = PhysicsParams::from(&*sim_config);
+    let force_matrix = config.flattened_force_matrices();
+    let type_properties = config.flattened_type_properties();
+    let field = ForceField {
+        params: &params,
+        force_matrix: &force_matrix,
+        type_properties: &type_properties,
+        num_types: config.num_types,
+        world_size: config.world_size,
+    };
+
+    let report = compare_with_brute_force(
+        &field,
+        GridParams::from(&*sim_config),
+        &snapshot.positions,
+        &snapshot.velocities,
+        &config.simulation_ranges,
+    );
+
+    if report.matches() {
+        println!("✅ Grille spatiale conforme sur {} particules (écart relatif max {:.2e})",
+                 report.particles, report.relative_error());
+    } else {
+        println!("❌ Grille spatiale divergente sur {} particules : écart max {:.4} pour une force max {:.4}",
+                 report.particles, report.max_error, report.max_force);
+    }
+}
+
+/// Nettoyage à la sortie de l'état Simulation (les entités sont `StateScoped`)
+pub fn reset_backend_sync(
+    mut backend_sync: ResMut<BackendSync>,
+    mut clock: ResMut<SimulationClock>,
+    mut snapshot: ResMut<ParticleSnapshot>,
+    mut previous: ResMut<PreviousSnapshot>,
+    mut spawner: ResMut<ParticleSpawner>,
+) {
+    *backend_sync = BackendSync::default();
+    *spawner = ParticleSpawner::default();
+    *clock = SimulationClock::default();
+    *snapshot = ParticleSnapshot::default();
+    *previous = PreviousSnapshot::default();
+}
+
+/// Crée les simulations et leur nourriture. Sans `Assets` (mode headless), les entités sont
+/// créées sans mesh ni matériau. Les particules elles-mêmes sont tirées par `upload_particles`,
+/// et chaque simulation est dessinée d'un bloc par son `ParticleInstances`.
 pub fn setup_simulations_from_config(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    meshes: Option<ResMut<Assets<Mesh>>>,
+    materials: Option<ResMut<Assets<StandardMaterial>>>,
     sim_config: Res<SimulationConfig>,
-    particle_types: Res<ParticleTypesConfig>,
     mut particle_config: ResMut<ParticleConfig>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
+    // Config chargée telle quelle : rien n'est créé, et la population vide n'est jamais tirée
+    if let Err(error) = sim_config.validate() {
+        eprintln!("❌ Configuration refusée : {}", error);
+        particle_config.num_particles = 0;
+        particle_config.simulation_ranges.clear();
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+
     // Mettre à jour la config des particules
-    particle_config.num_particles = sim_config.particle_count as u32;
+    particle_config.num_particles = (sim_config.particles_per_simulation * sim_config.simulation_count) as u32;
     particle_config.world_size = sim_config.world_size;
-    particle_config.num_types = sim_config.particle_types as u32;
-    particle_config.generate_random_forces();
+    particle_config.resize(sim_config.simulation_count as u32, sim_config.particle_types as u32);
+    // Un générateur par simulation et par usage, tous dérivés de la graine
+    println!("🌱 Graine: {}", sim_config.seed);
+    let mut rngs = SimulationRngs::new(sim_config.seed, sim_config.simulation_count as u32);
+
+    particle_config.generate_random_forces(&mut rngs);
+    particle_config.simulation_ranges.clear();
 
-    let mut rng = rand::rng();
     let mut global_particle_index = 0u32;
 
     // Mesh et matériaux
-    let particle_mesh = meshes.add(Mesh::from(Sphere::new(crate::globals::DEFAULT_PARTICLE_SIZE)));
-    let food_mesh = meshes.add(Mesh::from(Sphere::new(0.2)));
-
-    let mut particle_materials = Vec::new();
-    for i in 0..sim_config.particle_types {
-        let (color, emissive) = particle_types.get_color_for_type(i);
-        let material = materials.add(StandardMaterial {
-            base_color: color,
-            emissive,
-            metallic: 0.1,
+    let render_assets = meshes.zip(materials).map(|(mut meshes, mut materials)| {
+        // En 2D, des disques plats face à la caméra vue de dessus
+        let (particle_mesh, food_mesh) = match sim_config.dimension {
+            Dimension::Two => (
+                meshes.add(Mesh::from(Circle::new(crate::globals::DEFAULT_PARTICLE_SIZE))),
+                meshes.add(Mesh::from(Circle::new(0.2))),
+            ),
+            Dimension::Three => (
+                meshes.add(Mesh::from(Sphere::new(crate::globals::DEFAULT_PARTICLE_SIZE))),
+                meshes.add(Mesh::from(Sphere::new(0.2))),
+            ),
+        };
+
+        let food_material = materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            emissive: LinearRgba::WHITE,
             ..default()
         });
-        particle_materials.push(material);
-    }
 
-    let food_material = materials.add(StandardMaterial {
-        base_color: Color::WHITE,
-        emissive: LinearRgba::WHITE,
-        ..default()
+        (particle_mesh, food_mesh, food_material)
     });
 
     // Spawn simulations
@@ -88,55 +376,135 @@ pub fn setup_simulations_from_config(
             Transform::default(),
             Visibility::Visible,
             RenderLayers::layer(render_layer as Layer),
+            StateScoped(AppState::Simulation),
         )).id();
 
-        commands.entity(simulation_entity).with_children(|parent| {
-            // Particules
-            for _ in 0..sim_config.particles_per_simulation {
-                let particle_type = (global_particle_index % sim_config.particle_types as u32) as u32;
-
-                let x = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
-                let y = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
-                let z = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
-                let position = Vec3::new(x, y, z);
-
-                let material = particle_materials.get(particle_type as usize)
-                    .cloned()
-                    .unwrap_or_else(|| particle_materials[0].clone());
-
-                parent.spawn((
-                    LifeParticle::new(particle_type, global_particle_index),
-                    Mesh3d(particle_mesh.clone()),
-                    MeshMaterial3d(material),
-                    Transform::from_translation(position),
-                    Visibility::Inherited,
-                    RenderLayers::layer(render_layer as Layer),
-                ));
+        particle_config.simulation_ranges.push([
+            global_particle_index,
+            sim_config.particles_per_simulation as u32,
+        ]);
 
-                global_particle_index += 1;
+        let Some(rng) = rngs.get_mut(sim_id as u32) else {
+            continue;
+        };
+
+        let offset = global_particle_index;
+        global_particle_index += sim_config.particles_per_simulation as u32;
+
+        commands.entity(simulation_entity).with_children(|parent| {
+            if let Some((particle_mesh, _, _)) = &render_assets {
+                parent.spawn(ParticleInstances {
+                    simulation_id: sim_id as u32,
+                    offset,
+                    count: sim_config.particles_per_simulation as u32,
+                    mesh: particle_mesh.clone(),
+                });
             }
 
             // Nourriture
             for _ in 0..sim_config.food_count {
-                let x = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
-                let y = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
-                let z = rng.random::<f32>() * sim_config.world_size - sim_config.world_size * 0.5;
+                let position = sim_config.dimension.random_point(&mut rng.food, sim_config.world_size);
 
-                parent.spawn((
+                let mut food = parent.spawn((
                     Food {
                         value: sim_config.food_value,
                         simulation_id: sim_id as u32
                     },
                     FoodRespawnTimer::default(),
-                    Mesh3d(food_mesh.clone()),
-                    MeshMaterial3d(food_material.clone()),
-                    Transform::from_translation(Vec3::new(x, y, z)),
+                    Transform::from_translation(position),
                     Visibility::Inherited,
                     RenderLayers::layer(render_layer as Layer),
                 ));
+
+                if let Some((_, food_mesh, food_material)) = &render_assets {
+                    food.insert((Mesh3d(food_mesh.clone()), MeshMaterial3d(food_material.clone())));
+                }
             }
         });
     }
 
+    commands.insert_resource(rngs);
+    commands.insert_resource(ParticleSpawner::default());
+
     println!("Setup {} simulations with configuration", sim_config.simulation_count);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::systems::evolution_system::{EpochEnded, end_epoch};
+
+    const EPOCH_DURATION: f32 = 1.0;
+
+    /// Backend sans physique relisant ses lots comme le GPU : deux créneaux, et chaque lot
+    /// n'est disponible qu'à la frame suivant sa demande
+    #[derive(Resource, Default)]
+    struct LaggingBackend {
+        /// Âge en frames des lots non relus, du plus ancien au plus récent
+        batches: VecDeque<u32>,
+        total_steps: u64,
+    }
+
+    impl SimulationBackend for LaggingBackend {
+        fn upload_particles(&mut self, _: &[[f32; 4]], _: &[[f32; 4]], _: &[[u32; 2]]) {}
+
+        fn set_force_matrices(&mut self, _: &[f32]) {}
+
+        fn set_type_properties(&mut self, _: &[TypeProperties]) {}
+
+        fn set_physics(&mut self, _: &SimulationConfig) {}
+
+        fn step(&mut self, steps: u32, _: u32) {
+            self.total_steps += steps as u64;
+            self.batches.push_back(0);
+        }
+
+        fn poll_snapshot(&mut self) -> Option<ParticleSnapshot> {
+            self.batches.iter_mut().for_each(|age| *age += 1);
+            if self.batches.front().is_none_or(|&age| age < 2) {
+                return None;
+            }
+            self.batches.pop_front();
+            Some(ParticleSnapshot::default())
+        }
+
+        fn max_batches_in_flight(&self) -> u32 {
+            2
+        }
+    }
+
+    #[test]
+    fn epoch_ends_with_two_batches_in_flight() {
+        let mut app = App::new();
+        app.add_event::<EpochEnded>()
+            .insert_resource(SimulationConfig { epoch_duration: EPOCH_DURATION, ..default() })
+            .insert_resource(BackendSync { initialized: true, ..default() })
+            .init_resource::<LaggingBackend>()
+            .init_resource::<SimulationClock>()
+            .init_resource::<Generation>()
+            .init_resource::<ParticleSnapshot>()
+            .init_resource::<PreviousSnapshot>()
+            .init_resource::<Time>()
+            .init_resource::<Time<Real>>()
+            .add_systems(Update, (step_simulation::<LaggingBackend>, publish_snapshot::<LaggingBackend>, end_epoch).chain());
+
+        // Des frames de 2,5 pas : le dernier lot de l'époque doit être écourté
+        let mut frames = 0;
+        while app.world().resource::<Generation>().number == 0 {
+            frames += 1;
+            assert!(frames < 200, "l'époque ne se termine pas");
+            app.world_mut().resource_mut::<Time>().advance_by(Duration::from_secs_f32(2.5 * PHYSICS_TIMESTEP));
+            app.update();
+        }
+
+        let steps = (EPOCH_DURATION / PHYSICS_TIMESTEP).round() as u64;
+        let world = app.world();
+        assert_eq!(world.resource::<SimulationClock>().total_steps, steps);
+        assert_eq!(world.resource::<LaggingBackend>().total_steps, steps);
+        assert_eq!(world.resource::<Generation>().started_at_step, steps);
+        assert_eq!(world.resource::<BackendSync>().in_flight, 0);
+    }
+}
@@ -1,11 +1,14 @@
 use bevy::prelude::*;
+use bevy::render::extract_component::ExtractComponent;
 use bevy::render::view::{Layer, RenderLayers};
-use bevy::render::camera::{Viewport, ClearColorConfig};
+use bevy::render::camera::{Viewport, ClearColorConfig, ScalingMode};
 use bevy::window::WindowResized;
 
-use crate::resources::simulation_config::SimulationConfig;
+use crate::resources::simulation_config::{Dimension, SimulationConfig};
+use crate::states::app_state::AppState;
 
-#[derive(Component)]
+/// Extrait vers le monde de rendu : chaque caméra ne dessine que les particules de sa simulation
+#[derive(Component, Clone, ExtractComponent)]
 pub struct SimulationCamera {
     pub simulation_id: u32,
 }
@@ -36,11 +39,24 @@ pub fn setup_viewports(
 
         // Position caméra pour voir toute la simulation
         let camera_distance = sim_config.world_size * 1.5;
-        let camera_pos = Vec3::new(
-            camera_distance * 0.7,
-            camera_distance * 0.5,
-            camera_distance * 0.7,
-        );
+        let (camera_transform, projection) = match sim_config.dimension {
+            // Vue de dessus orthographique, le carré du monde entier dans le viewport
+            Dimension::Two => (
+                Transform::from_xyz(0.0, 0.0, camera_distance).looking_at(Vec3::ZERO, Vec3::Y),
+                Projection::from(OrthographicProjection {
+                    scaling_mode: ScalingMode::AutoMin {
+                        min_width: sim_config.world_size * 1.1,
+                        min_height: sim_config.world_size * 1.1,
+                    },
+                    ..OrthographicProjection::default_3d()
+                }),
+            ),
+            Dimension::Three => (
+                Transform::from_xyz(camera_distance * 0.7, camera_distance * 0.5, camera_distance * 0.7)
+                    .looking_at(Vec3::ZERO, Vec3::Y),
+                Projection::default(),
+            ),
+        };
 
         commands.spawn((
             Camera {
@@ -54,9 +70,11 @@ pub fn setup_viewports(
                 ..default()
             },
             Camera3d::default(),
-            Transform::from_translation(camera_pos).looking_at(Vec3::ZERO, Vec3::Y),
+            projection,
+            camera_transform,
             SimulationCamera { simulation_id: sim_id as u32 },
             RenderLayers::from_layers(&[0, (sim_id + 1) as Layer]),
+            StateScoped(AppState::Simulation),
         ));
     }
 
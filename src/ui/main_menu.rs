@@ -1,40 +1,86 @@
 use bevy::prelude::*;
+use bevy::render::renderer::RenderDevice;
 use bevy_egui::{egui, EguiContexts};
-use crate::resources::simulation_config::{SimulationConfig, ParticleTypesConfig};
+use crate::plugins::particle_life_plugin::gpu_particle_capacity;
+use crate::resources::simulation_config::{BackendKind, BoundaryMode, Dimension, ForceModel, MutationMethod, CrossoverMethod, FitnessObjective, FitnessWeights, NeighborSearch, SelectionMethod, SimulationConfig, ParticleTypesConfig};
 use crate::states::app_state::AppState;
 use crate::globals::*;
 
 #[derive(Resource)]
 pub struct MenuConfig {
     pub world_size: f32,
+    pub dimension: Dimension,
     pub simulation_count: usize,
     pub particle_count: usize,
     pub particle_types: usize,
+    pub seed: u64,
+    pub backend: BackendKind,
+    pub time_scale: f32,
+    pub substeps: u32,
+    pub force_model: ForceModel,
+    pub force_beta: f32,
     pub max_force_range: f32,
+    pub velocity_half_life: f32,
+    pub neighbor_search: NeighborSearch,
+    pub boundary_mode: BoundaryMode,
+    pub restitution: f32,
+    pub wall_stiffness: f32,
+    pub centering_strength: f32,
     pub food_count: usize,
     pub food_respawn_enabled: bool,
     pub food_respawn_time: f32,
     pub food_value: f32,
+    pub epoch_duration: f32,
     pub elite_ratio: f32,
+    pub selection: SelectionMethod,
+    pub tournament_size: usize,
+    pub truncation_ratio: f32,
+    pub mutation: MutationMethod,
     pub mutation_rate: f32,
+    pub mutation_sigma: f32,
+    pub crossover: CrossoverMethod,
     pub crossover_rate: f32,
+    pub blend_alpha: f32,
+    pub fitness_weights: FitnessWeights,
 }
 
 impl Default for MenuConfig {
     fn default() -> Self {
         Self {
             world_size: DEFAULT_WORLD_SIZE,
+            dimension: Dimension::default(),
             simulation_count: DEFAULT_SIMULATION_COUNT,
             particle_count: DEFAULT_PARTICLE_COUNT,
             particle_types: DEFAULT_PARTICLE_TYPES,
+            seed: rand::random(),
+            backend: BackendKind::default(),
+            time_scale: DEFAULT_TIME_SCALE,
+            substeps: DEFAULT_SUBSTEPS,
+            force_model: ForceModel::default(),
+            force_beta: DEFAULT_FORCE_BETA,
             max_force_range: DEFAULT_MAX_FORCE_RANGE,
+            velocity_half_life: VELOCITY_HALF_LIFE,
+            neighbor_search: NeighborSearch::default(),
+            boundary_mode: BoundaryMode::default(),
+            restitution: DEFAULT_RESTITUTION,
+            wall_stiffness: DEFAULT_WALL_STIFFNESS,
+            centering_strength: DEFAULT_CENTERING_STRENGTH,
             food_count: DEFAULT_FOOD_COUNT,
             food_respawn_enabled: true,
             food_respawn_time: DEFAULT_FOOD_RESPAWN_TIME,
             food_value: DEFAULT_FOOD_VALUE,
+            epoch_duration: DEFAULT_EPOCH_DURATION,
             elite_ratio: DEFAULT_ELITE_RATIO,
+            selection: SelectionMethod::default(),
+            tournament_size: DEFAULT_TOURNAMENT_SIZE,
+            truncation_ratio: DEFAULT_TRUNCATION_RATIO,
+            mutation: MutationMethod::default(),
             mutation_rate: DEFAULT_MUTATION_RATE,
+            mutation_sigma: DEFAULT_MUTATION_SIGMA,
+            crossover: CrossoverMethod::default(),
             crossover_rate: DEFAULT_CROSSOVER_RATE,
+            blend_alpha: DEFAULT_BLEND_ALPHA,
+            fitness_weights: FitnessWeights::default(),
         }
     }
 }
@@ -44,10 +90,20 @@ pub fn main_menu_ui(
     mut menu_config: ResMut<MenuConfig>,
     mut next_state: ResMut<NextState<AppState>>,
     mut commands: Commands,
+    render_device: Option<Res<RenderDevice>>,
 ) {
 
     println!("MainMenu ui");
 
+    // Sur GPU, la population n'est bornée que par la taille des buffers acceptée par le device
+    let max_particles = match (menu_config.backend, render_device) {
+        (BackendKind::Gpu, Some(render_device)) => gpu_particle_capacity(&render_device.limits()),
+        _ => MAX_PARTICLE_COUNT,
+    };
+    if menu_config.particle_count > max_particles {
+        menu_config.particle_count = max_particles;
+    }
+
     let ctx = contexts.ctx_mut();
 
     egui::CentralPanel::default().show(ctx, |ui| {
@@ -81,6 +137,14 @@ pub fn main_menu_ui(
                             .range(20.0..=200.0)
                             .suffix(" unités"));
                         ui.end_row();
+
+                        ui.label("Dimensions:");
+                        ui.horizontal(|ui| {
+                            for dimension in Dimension::ALL {
+                                ui.selectable_value(&mut menu_config.dimension, dimension, dimension.label());
+                            }
+                        });
+                        ui.end_row();
                     });
             });
 
@@ -97,12 +161,14 @@ pub fn main_menu_ui(
                     .show(ui, |ui| {
                         ui.label("Nombre de simulations:");
                         ui.add(egui::DragValue::new(&mut menu_config.simulation_count)
-                            .range(1..=16));
+                            .range(1..=MAX_SIMULATIONS));
                         ui.end_row();
 
                         ui.label("Particules totales:");
                         ui.add(egui::DragValue::new(&mut menu_config.particle_count)
-                            .range(100..=3200));
+                            .range(100..=max_particles)
+                            .speed(100.0))
+                            .on_hover_text(format!("Au plus {} avec ce moteur physique", max_particles));
                         ui.end_row();
 
                         ui.label("Types de particules:");
@@ -110,11 +176,119 @@ pub fn main_menu_ui(
                             .range(2..=8));
                         ui.end_row();
 
+                        ui.label("Graine:");
+                        ui.horizontal(|ui| {
+                            ui.add(egui::DragValue::new(&mut menu_config.seed).speed(0.0));
+                            if ui.small_button("🎲").on_hover_text("Nouvelle graine").clicked() {
+                                menu_config.seed = rand::random();
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Moteur physique:");
+                        egui::ComboBox::from_id_salt("backend")
+                            .selected_text(menu_config.backend.label())
+                            .show_ui(ui, |ui| {
+                                for kind in BackendKind::ALL {
+                                    ui.selectable_value(&mut menu_config.backend, kind, kind.label());
+                                }
+                            });
+                        ui.end_row();
+
+                        // Les sous-pas d'une frame doivent tenir dans un lot
+                        let max_time_scale = SimulationConfig::max_time_scale(menu_config.substeps);
+                        let max_substeps = SimulationConfig::max_substeps(menu_config.time_scale);
+
+                        ui.label("Vitesse de simulation:");
+                        ui.add(egui::Slider::new(&mut menu_config.time_scale, MIN_TIME_SCALE..=max_time_scale)
+                            .logarithmic(true)
+                            .suffix("×"));
+                        ui.end_row();
+
+                        ui.label("Sous-pas par pas:");
+                        ui.add(egui::DragValue::new(&mut menu_config.substeps)
+                            .range(1..=max_substeps));
+                        ui.end_row();
+
                         ui.label("Portée des forces:");
                         ui.add(egui::DragValue::new(&mut menu_config.max_force_range)
-                            .range(10.0..=200.0)
+                            .range(1.0..=200.0)
                             .suffix(" unités"));
                         ui.end_row();
+
+                        ui.label("Demi-vie de la vitesse:");
+                        ui.add(egui::DragValue::new(&mut menu_config.velocity_half_life)
+                            .range(0.005..=2.0)
+                            .speed(0.001)
+                            .fixed_decimals(3)
+                            .suffix(" sec"));
+                        ui.end_row();
+
+                        ui.label("Modèle de force:");
+                        egui::ComboBox::from_id_salt("force_model")
+                            .selected_text(menu_config.force_model.label())
+                            .show_ui(ui, |ui| {
+                                for model in ForceModel::ALL {
+                                    ui.selectable_value(&mut menu_config.force_model, model, model.label());
+                                }
+                            });
+                        ui.end_row();
+
+                        if menu_config.force_model == ForceModel::ParticleLife {
+                            ui.label("Répulsion β:");
+                            ui.add(egui::DragValue::new(&mut menu_config.force_beta)
+                                .range(0.05..=0.9)
+                                .speed(0.01)
+                                .fixed_decimals(2));
+                            ui.end_row();
+                        }
+
+                        ui.label("Recherche des voisines:");
+                        egui::ComboBox::from_id_salt("neighbor_search")
+                            .selected_text(menu_config.neighbor_search.label())
+                            .show_ui(ui, |ui| {
+                                for mode in NeighborSearch::ALL {
+                                    ui.selectable_value(&mut menu_config.neighbor_search, mode, mode.label());
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Bords du monde:");
+                        egui::ComboBox::from_id_salt("boundary_mode")
+                            .selected_text(menu_config.boundary_mode.label())
+                            .show_ui(ui, |ui| {
+                                for mode in BoundaryMode::ALL {
+                                    ui.selectable_value(&mut menu_config.boundary_mode, mode, mode.label());
+                                }
+                            });
+                        ui.end_row();
+
+                        match menu_config.boundary_mode {
+                            BoundaryMode::Reflect => {
+                                ui.label("Restitution:");
+                                ui.add(egui::DragValue::new(&mut menu_config.restitution)
+                                    .range(0.0..=1.0)
+                                    .speed(0.01)
+                                    .fixed_decimals(2));
+                                ui.end_row();
+                            }
+                            BoundaryMode::SoftWall => {
+                                ui.label("Raideur des murs:");
+                                ui.add(egui::DragValue::new(&mut menu_config.wall_stiffness)
+                                    .range(1.0..=1000.0)
+                                    .speed(1.0));
+                                ui.end_row();
+                            }
+                            BoundaryMode::Unbounded => {
+                                ui.label("Rappel au centre:");
+                                ui.add(egui::DragValue::new(&mut menu_config.centering_strength)
+                                    .range(0.0..=10.0)
+                                    .speed(0.01)
+                                    .fixed_decimals(2));
+                                ui.end_row();
+                            }
+                            BoundaryMode::Wrap => {}
+                        }
                     });
 
                 ui.add_space(5.0);
@@ -135,6 +309,14 @@ pub fn main_menu_ui(
                     .num_columns(3)
                     .spacing([10.0, 8.0])
                     .show(ui, |ui| {
+                        ui.label("Durée d'une époque:");
+                        ui.add(egui::DragValue::new(&mut menu_config.epoch_duration)
+                            .range(5.0..=600.0)
+                            .speed(1.0)
+                            .suffix(" sec"));
+                        ui.label("(temps simulé)");
+                        ui.end_row();
+
                         ui.label("Élites:");
                         ui.add(egui::DragValue::new(&mut menu_config.elite_ratio)
                             .range(0.01..=0.5)
@@ -143,6 +325,37 @@ pub fn main_menu_ui(
                         ui.label(format!("({:.0}%)", menu_config.elite_ratio * 100.0));
                         ui.end_row();
 
+                        ui.label("Sélection:");
+                        egui::ComboBox::from_id_salt("selection_method")
+                            .selected_text(menu_config.selection.label())
+                            .show_ui(ui, |ui| {
+                                for method in SelectionMethod::ALL {
+                                    ui.selectable_value(&mut menu_config.selection, method, method.label());
+                                }
+                            });
+                        ui.label("");
+                        ui.end_row();
+
+                        match menu_config.selection {
+                            SelectionMethod::Tournament => {
+                                ui.label("Taille du tournoi:");
+                                ui.add(egui::DragValue::new(&mut menu_config.tournament_size)
+                                    .range(1..=MAX_SIMULATIONS));
+                                ui.label("simulations");
+                                ui.end_row();
+                            }
+                            SelectionMethod::Truncation => {
+                                ui.label("Fraction gardée:");
+                                ui.add(egui::DragValue::new(&mut menu_config.truncation_ratio)
+                                    .range(0.05..=1.0)
+                                    .speed(0.01)
+                                    .fixed_decimals(2));
+                                ui.label(format!("({:.0}%)", menu_config.truncation_ratio * 100.0));
+                                ui.end_row();
+                            }
+                            SelectionMethod::Roulette | SelectionMethod::Rank => {}
+                        }
+
                         ui.label("Mutation:");
                         ui.add(egui::DragValue::new(&mut menu_config.mutation_rate)
                             .range(0.0..=1.0)
@@ -151,6 +364,27 @@ pub fn main_menu_ui(
                         ui.label(format!("({:.0}%)", menu_config.mutation_rate * 100.0));
                         ui.end_row();
 
+                        ui.label("Opérateur de mutation:");
+                        egui::ComboBox::from_id_salt("mutation_method")
+                            .selected_text(menu_config.mutation.label())
+                            .show_ui(ui, |ui| {
+                                for method in MutationMethod::ALL {
+                                    ui.selectable_value(&mut menu_config.mutation, method, method.label());
+                                }
+                            });
+                        ui.label("");
+                        ui.end_row();
+
+                        if menu_config.mutation == MutationMethod::Gaussian {
+                            ui.label("Écart type:");
+                            ui.add(egui::DragValue::new(&mut menu_config.mutation_sigma)
+                                .range(0.01..=2.0)
+                                .speed(0.01)
+                                .fixed_decimals(2));
+                            ui.label("(force)");
+                            ui.end_row();
+                        }
+
                         ui.label("Crossover:");
                         ui.add(egui::DragValue::new(&mut menu_config.crossover_rate)
                             .range(0.0..=1.0)
@@ -158,11 +392,58 @@ pub fn main_menu_ui(
                             .fixed_decimals(2));
                         ui.label(format!("({:.0}%)", menu_config.crossover_rate * 100.0));
                         ui.end_row();
+
+                        ui.label("Opérateur de crossover:");
+                        egui::ComboBox::from_id_salt("crossover_method")
+                            .selected_text(menu_config.crossover.label())
+                            .show_ui(ui, |ui| {
+                                for method in CrossoverMethod::ALL {
+                                    ui.selectable_value(&mut menu_config.crossover, method, method.label());
+                                }
+                            });
+                        ui.label("");
+                        ui.end_row();
+
+                        if menu_config.crossover == CrossoverMethod::Blend {
+                            ui.label("α:");
+                            ui.add(egui::DragValue::new(&mut menu_config.blend_alpha)
+                                .range(0.0..=1.0)
+                                .speed(0.01)
+                                .fixed_decimals(2));
+                            ui.label("(élargissement)");
+                            ui.end_row();
+                        }
                     });
             });
 
             ui.add_space(10.0);
 
+            // Objectifs de l'algorithme génétique
+            ui.group(|ui| {
+                ui.label(egui::RichText::new("🎯 Objectifs").size(16.0).strong());
+                ui.separator();
+
+                egui::Grid::new("fitness_weights")
+                    .num_columns(2)
+                    .spacing([10.0, 8.0])
+                    .show(ui, |ui| {
+                        for objective in FitnessObjective::ALL {
+                            ui.label(format!("{}:", objective.label()));
+                            ui.add(egui::DragValue::new(menu_config.fitness_weights.weight_mut(objective))
+                                .range(-10.0..=10.0)
+                                .speed(0.05)
+                                .fixed_decimals(2));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.label(egui::RichText::new("Score = somme pondérée des objectifs ; un poids nul désactive l'objectif")
+                    .small()
+                    .color(egui::Color32::GRAY));
+            });
+
+            ui.add_space(10.0);
+
             // Paramètres de nourriture
             ui.group(|ui| {
                 ui.label(egui::RichText::new("🍎 Nourriture").size(16.0).strong());
@@ -236,25 +517,50 @@ pub fn main_menu_ui(
 fn apply_configuration(commands: &mut Commands, config: &MenuConfig) {
     commands.insert_resource(SimulationConfig {
         world_size: config.world_size,
+        dimension: config.dimension,
         simulation_count: config.simulation_count,
         particle_count: config.particle_count,
         particle_types: config.particle_types,
         particles_per_simulation: config.particle_count / config.simulation_count,
+        seed: config.seed,
+        backend: config.backend,
+        time_scale: config.time_scale,
+        substeps: config.substeps,
+        force_model: config.force_model,
+        force_beta: config.force_beta,
         max_force_range: config.max_force_range,
-        velocity_half_life: VELOCITY_HALF_LIFE,
+        velocity_half_life: config.velocity_half_life,
+        force_scale: DEFAULT_FORCE_SCALE,
+        max_velocity: DEFAULT_MAX_VELOCITY,
+        min_distance: DEFAULT_MIN_DISTANCE,
+        neighbor_search: config.neighbor_search,
+        boundary_mode: config.boundary_mode,
+        restitution: config.restitution,
+        wall_stiffness: config.wall_stiffness,
+        centering_strength: config.centering_strength,
         food_count: config.food_count,
         food_respawn_enabled: config.food_respawn_enabled,
         food_respawn_time: config.food_respawn_time,
         food_value: config.food_value,
+        epoch_duration: config.epoch_duration,
         elite_ratio: config.elite_ratio,
+        selection: config.selection,
+        tournament_size: config.tournament_size,
+        truncation_ratio: config.truncation_ratio,
+        mutation: config.mutation,
         mutation_rate: config.mutation_rate,
+        mutation_sigma: config.mutation_sigma,
+        crossover: config.crossover,
         crossover_rate: config.crossover_rate,
+        blend_alpha: config.blend_alpha,
+        fitness_weights: config.fitness_weights,
         viewport_cols: if config.simulation_count <= 4 {
             config.simulation_count.min(4) as u32
         } else { 4 },
         viewport_rows: if config.simulation_count <= 4 { 1 } else {
             ((config.simulation_count + 3) / 4) as u32
         },
+        render_interpolation: true,
     });
 
     commands.insert_resource(ParticleTypesConfig::new(config.particle_types));
@@ -262,5 +568,6 @@ fn apply_configuration(commands: &mut Commands, config: &MenuConfig) {
     info!("Configuration appliquée:");
     info!("  • {} simulations avec {} particules totales", config.simulation_count, config.particle_count);
     info!("  • {} types de particules", config.particle_types);
-    info!("  • Monde {}x{} unités", config.world_size, config.world_size);
+    info!("  • Graine {}", config.seed);
+    info!("  • Monde {} {}x{} unités", config.dimension.label(), config.world_size, config.world_size);
 }
\ No newline at end of file
@@ -1,17 +1,30 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 use std::collections::HashSet;
+use std::path::Path;
 
 use crate::components::simulation::Simulation;
+use crate::genetics::genome::Genome;
 use crate::resources::particle_config::ParticleConfig;
+use crate::resources::physics_params::{GridParams, TypeProperties};
+use crate::globals::*;
+use crate::resources::simulation_config::{BoundaryMode, ForceModel, NeighborSearch, SimulationConfig};
+use crate::resources::simulation_rng::SimulationRngs;
+use crate::systems::evolution_system::Generation;
+use crate::systems::simulation_system::SimulationClock;
 
 #[derive(Resource)]
 pub struct SimulationUI {
     pub selected_simulation: Option<usize>,
     pub show_matrix_window: bool,
     pub show_simulations_list: bool,
+    pub show_physics_window: bool,
     pub selected_simulations: HashSet<usize>,
     pub right_panel_width: f32,
+    /// Fichier des boutons d'import / export de génome
+    pub genome_path: String,
+    /// Résultat du dernier import ou export
+    pub genome_status: Option<Result<String, String>>,
 }
 
 impl Default for SimulationUI {
@@ -23,16 +36,29 @@ impl Default for SimulationUI {
             selected_simulation: None,
             show_matrix_window: false,
             show_simulations_list: true,
+            show_physics_window: false,
             selected_simulations,
             right_panel_width: 0.0,
+            genome_path: String::from("genome.ron"),
+            genome_status: None,
         }
     }
 }
 
+/// Oublie la sélection : les simulations seront recréées au prochain lancement
+pub fn reset_simulation_ui(mut ui_state: ResMut<SimulationUI>) {
+    ui_state.selected_simulation = None;
+    ui_state.show_matrix_window = false;
+    ui_state.genome_status = None;
+}
+
 pub fn simulations_list_ui(
     mut contexts: EguiContexts,
     mut ui_state: ResMut<SimulationUI>,
+    sim_config: Option<Res<SimulationConfig>>,
     simulations: Query<&Simulation>,
+    generation: Res<Generation>,
+    clock: Res<SimulationClock>,
 ) {
     let ctx = contexts.ctx_mut();
 
@@ -47,7 +73,31 @@ pub fn simulations_list_ui(
         .exact_width(panel_width)
         .resizable(false)
         .show(ctx, |ui| {
-            ui.heading("🔬 Simulations");
+            ui.horizontal(|ui| {
+                ui.heading("🔬 Simulations");
+                if ui.button("⚙ Physique").on_hover_text("Paramètres physiques en direct").clicked() {
+                    ui_state.show_physics_window = !ui_state.show_physics_window;
+                }
+            });
+
+            if let Some(sim_config) = &sim_config {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("🌱 Graine: {}", sim_config.seed))
+                        .small()
+                        .color(egui::Color32::GRAY));
+                    if ui.small_button("📋").on_hover_text("Copier la graine").clicked() {
+                        ui.ctx().copy_text(sim_config.seed.to_string());
+                    }
+                });
+
+                // Temps simulé : le compte à rebours suit la vitesse de simulation
+                let remaining = generation.remaining(&clock, sim_config.epoch_duration);
+                ui.label(egui::RichText::new(format!(
+                    "🧬 Génération {} — prochaine dans {:.0} s",
+                    generation.number + 1,
+                    remaining.ceil()
+                )).strong());
+            }
 
             ui.horizontal(|ui| {
                 if ui.button("Tout sélectionner").clicked() {
@@ -149,7 +199,9 @@ pub fn simulations_list_ui(
 pub fn force_matrix_window(
     mut contexts: EguiContexts,
     mut ui_state: ResMut<SimulationUI>,
-    config: Res<ParticleConfig>,
+    mut config: ResMut<ParticleConfig>,
+    mut rngs: ResMut<SimulationRngs>,
+    sim_config: Res<SimulationConfig>,
     simulations: Query<&Simulation>,
 ) {
     if !ui_state.show_matrix_window || ui_state.selected_simulation.is_none() {
@@ -158,6 +210,8 @@ pub fn force_matrix_window(
 
     let ctx = contexts.ctx_mut();
     let selected_sim = ui_state.selected_simulation.unwrap();
+    // Emprunts séparés : la fenêtre garde `show_matrix_window`, son contenu le chemin du génome
+    let ui_state = &mut *ui_state;
 
     egui::Window::new(format!("🔬 Matrice des Forces - Simulation #{}", selected_sim + 1))
         .resizable(true)
@@ -166,10 +220,49 @@ pub fn force_matrix_window(
         .open(&mut ui_state.show_matrix_window)
         .show(ctx, |ui| {
             if let Some(simulation) = simulations.iter().nth(selected_sim) {
+                let sim = simulation.id;
+
                 ui.label(format!("Types de particules: {}", config.num_types));
                 ui.label(egui::RichText::new("Forces normalisées entre -2.000 et +2.000")
                     .small()
                     .color(egui::Color32::from_rgb(150, 150, 150)));
+
+                // Tirages issus du générateur de la simulation : rejouer la graine rejoue ces clics
+                ui.horizontal(|ui| {
+                    if let Some(rng) = rngs.get_mut(sim) {
+                        if ui.button("🎲 Aléatoire").on_hover_text("Regénérer cette matrice").clicked() {
+                            config.generate_random_forces_for(sim, &mut rng.forces);
+                        }
+                        if ui.button("✨ Préréglage").on_hover_text("Forces intéressantes prédéfinies").clicked() {
+                            config.set_interesting_forces(sim, &mut rng.forces);
+                        }
+                    }
+                });
+
+                // Règles complètes de la simulation (matrice et propriétés des types), au format versionné
+                ui.horizontal(|ui| {
+                    ui.label("🧬 Génome:");
+                    ui.text_edit_singleline(&mut ui_state.genome_path);
+                    let path = Path::new(&ui_state.genome_path);
+                    if ui.button("💾 Exporter").on_hover_text("Enregistrer les règles de cette simulation").clicked() {
+                        ui_state.genome_status = Some(config.genomes.get(sim as usize)
+                            .ok_or_else(|| format!("Simulation #{} inconnue", sim + 1))
+                            .and_then(|genome| genome.save(path))
+                            .map(|()| format!("Génome exporté dans {}", path.display())));
+                    }
+                    if ui.button("📂 Importer").on_hover_text("Remplacer les règles de cette simulation").clicked() {
+                        ui_state.genome_status = Some(import_genome(&mut config, sim, path));
+                    }
+                });
+                match &ui_state.genome_status {
+                    Some(Ok(message)) => {
+                        ui.label(egui::RichText::new(message).small().color(egui::Color32::from_rgb(0, 200, 0)));
+                    }
+                    Some(Err(error)) => {
+                        ui.label(egui::RichText::new(error).small().color(egui::Color32::from_rgb(255, 80, 80)));
+                    }
+                    None => {}
+                }
                 ui.separator();
 
                 // Matrice des forces
@@ -211,7 +304,7 @@ pub fn force_matrix_window(
                                 .strong());
 
                             for j in 0..config.num_types {
-                                let force = config.get_force(i, j);
+                                let mut force = config.get_force(sim, i, j);
 
                                 let force_color = if force.abs() < 0.05 {
                                     egui::Color32::from_rgb(120, 120, 120)
@@ -223,10 +316,86 @@ pub fn force_matrix_window(
                                     egui::Color32::from_rgb(intensity.max(100), 0, 0)
                                 };
 
-                                ui.label(egui::RichText::new(format!("{:+.3}", force))
-                                    .color(force_color)
-                                    .monospace()
-                                    .size(11.0));
+                                // Édition directe : la valeur est recopiée sur le GPU par `sync_force_matrices`
+                                ui.scope(|ui| {
+                                    ui.visuals_mut().override_text_color = Some(force_color);
+                                    let response = ui.add(egui::DragValue::new(&mut force)
+                                        .range(-MAX_FORCE_WEIGHT..=MAX_FORCE_WEIGHT)
+                                        .speed(0.01)
+                                        .fixed_decimals(3));
+                                    if response.changed() {
+                                        config.set_force(sim, i, j, force);
+                                    }
+                                });
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(10.0);
+                ui.separator();
+
+                // Propriétés physiques, relatives aux paramètres globaux de la fenêtre Physique
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("⚛ Propriétés des Types").size(14.0).strong());
+                    if ui.button("⚛ Noyaux / électrons")
+                        .on_hover_text("Types pairs lourds et lents, types impairs légers et rapides")
+                        .clicked()
+                    {
+                        config.set_nuclei_and_electrons(sim);
+                    }
+                    if ui.button("↺ Neutres").on_hover_text("Toutes les propriétés à 1").clicked() {
+                        for particle_type in 0..config.num_types {
+                            config.set_type_properties(sim, particle_type, TypeProperties::default());
+                        }
+                    }
+                });
+                ui.add_space(5.0);
+
+                egui::Grid::new("type_properties_grid")
+                    .num_columns(5)
+                    .spacing([10.0, 4.0])
+                    .min_col_width(70.0)
+                    .show(ui, |ui| {
+                        ui.label("Type");
+                        ui.label("Masse").on_hover_text("Divise l'accélération");
+                        ui.label("Portée").on_hover_text("Fraction de la portée des forces subies");
+                        ui.label("Vitesse max").on_hover_text("Multiplie la vitesse max");
+                        ui.label("Amortissement").on_hover_text("0 : aucune friction, 2 : friction doublée");
+                        ui.end_row();
+
+                        for i in 0..config.num_types {
+                            let color = get_type_color(i as usize, config.num_types as usize);
+                            ui.label(egui::RichText::new(format!("T{}", i))
+                                .color(egui::Color32::from_rgb(
+                                    (color.to_srgba().red * 255.0) as u8,
+                                    (color.to_srgba().green * 255.0) as u8,
+                                    (color.to_srgba().blue * 255.0) as u8,
+                                ))
+                                .strong());
+
+                            // Recopiées sur le GPU par `sync_type_properties`
+                            let mut properties = config.get_type_properties(sim, i);
+                            let mut changed = false;
+                            changed |= ui.add(egui::DragValue::new(&mut properties.mass)
+                                .range(TypeProperties::MASS_RANGE)
+                                .speed(0.01)
+                                .fixed_decimals(2)).changed();
+                            changed |= ui.add(egui::DragValue::new(&mut properties.radius)
+                                .range(TypeProperties::RADIUS_RANGE)
+                                .speed(0.01)
+                                .fixed_decimals(2)).changed();
+                            changed |= ui.add(egui::DragValue::new(&mut properties.max_speed)
+                                .range(TypeProperties::MAX_SPEED_RANGE)
+                                .speed(0.01)
+                                .fixed_decimals(2)
+                                .suffix("×")).changed();
+                            changed |= ui.add(egui::DragValue::new(&mut properties.damping)
+                                .range(TypeProperties::DAMPING_RANGE)
+                                .speed(0.01)
+                                .fixed_decimals(2)).changed();
+                            if changed {
+                                config.set_type_properties(sim, i, properties);
                             }
                             ui.end_row();
                         }
@@ -238,13 +407,211 @@ pub fn force_matrix_window(
                 ui.collapsing("🔧 Détails techniques", |ui| {
                     ui.label(format!("Score actuel: {:.1}", simulation.score));
                     ui.label(format!("ID simulation: {}", simulation.id));
-                    ui.label(format!("Forces stockées: {}", config.force_matrix.len()));
+                    let stored = config.genomes.get(sim as usize).map_or(0, |genome| genome.force_matrix.len());
+                    ui.label(format!("Forces stockées: {}", stored));
                     ui.separator();
-                    ui.label(egui::RichText::new("Facteur d'échelle: 80.0").strong());
-                    ui.label("Forces réelles = valeurs × 80.0");
+                    ui.label(egui::RichText::new(format!("Facteur d'échelle: {:.1}", sim_config.force_scale)).strong());
+                    ui.label(format!("Forces réelles = valeurs × {:.1}", sim_config.force_scale));
+                });
+            }
+        });
+}
+
+/// Paramètres physiques modifiables pendant la simulation.
+/// Les changements sont envoyés au shader par `sync_physics_params`.
+pub fn physics_window(
+    mut contexts: EguiContexts,
+    mut ui_state: ResMut<SimulationUI>,
+    mut sim_config: ResMut<SimulationConfig>,
+    clock: Res<SimulationClock>,
+) {
+    if !ui_state.show_physics_window {
+        return
+    }
+
+    let ctx = contexts.ctx_mut();
+
+    // Copie locale : on ne marque la config modifiée que si une valeur change vraiment
+    let mut edited = sim_config.clone();
+
+    egui::Window::new("⚙ Paramètres physiques")
+        .resizable(false)
+        .collapsible(true)
+        .open(&mut ui_state.show_physics_window)
+        .show(ctx, |ui| {
+            egui::Grid::new("physics_params_grid")
+                .num_columns(2)
+                .spacing([10.0, 8.0])
+                .show(ui, |ui| {
+                    // Les sous-pas d'une frame doivent tenir dans un lot
+                    let max_time_scale = SimulationConfig::max_time_scale(edited.substeps);
+                    let max_substeps = SimulationConfig::max_substeps(edited.time_scale);
+
+                    ui.label("Vitesse de simulation:");
+                    ui.add(egui::Slider::new(&mut edited.time_scale, MIN_TIME_SCALE..=max_time_scale)
+                        .logarithmic(true)
+                        .suffix("×"));
+                    ui.end_row();
+
+                    ui.label("Sous-pas par pas:");
+                    ui.add(egui::DragValue::new(&mut edited.substeps)
+                        .range(1..=max_substeps));
+                    ui.end_row();
+
+                    ui.label("Modèle de force:");
+                    egui::ComboBox::from_id_salt("live_force_model")
+                        .selected_text(edited.force_model.label())
+                        .show_ui(ui, |ui| {
+                            for model in ForceModel::ALL {
+                                ui.selectable_value(&mut edited.force_model, model, model.label());
+                            }
+                        });
+                    ui.end_row();
+
+                    if edited.force_model == ForceModel::ParticleLife {
+                        ui.label("Répulsion β:");
+                        ui.add(egui::DragValue::new(&mut edited.force_beta)
+                            .range(0.05..=0.9)
+                            .speed(0.01)
+                            .fixed_decimals(2));
+                        ui.end_row();
+                    }
+
+                    ui.label("Portée des forces:");
+                    ui.add(egui::DragValue::new(&mut edited.max_force_range)
+                        .range(1.0..=200.0)
+                        .speed(0.1)
+                        .suffix(" unités"));
+                    ui.end_row();
+
+                    ui.label("Facteur de force:");
+                    ui.add(egui::DragValue::new(&mut edited.force_scale)
+                        .range(0.1..=200.0)
+                        .speed(0.1));
+                    ui.end_row();
+
+                    ui.label("Demi-vie de la vitesse:");
+                    ui.add(egui::DragValue::new(&mut edited.velocity_half_life)
+                        .range(0.005..=2.0)
+                        .speed(0.001)
+                        .fixed_decimals(3)
+                        .suffix(" sec"));
+                    ui.end_row();
+
+                    ui.label("Vitesse max:");
+                    ui.add(egui::DragValue::new(&mut edited.max_velocity)
+                        .range(1.0..=10000.0)
+                        .speed(1.0));
+                    ui.end_row();
+
+                    if edited.force_model == ForceModel::InverseSquare {
+                        ui.label("Distance min:");
+                        ui.add(egui::DragValue::new(&mut edited.min_distance)
+                            .range(0.01..=10.0)
+                            .speed(0.01)
+                            .fixed_decimals(2));
+                        ui.end_row();
+                    }
+
+                    ui.label("Recherche des voisines:");
+                    egui::ComboBox::from_id_salt("live_neighbor_search")
+                        .selected_text(edited.neighbor_search.label())
+                        .show_ui(ui, |ui| {
+                            for mode in NeighborSearch::ALL {
+                                ui.selectable_value(&mut edited.neighbor_search, mode, mode.label());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Bords du monde:");
+                    egui::ComboBox::from_id_salt("live_boundary_mode")
+                        .selected_text(edited.boundary_mode.label())
+                        .show_ui(ui, |ui| {
+                            for mode in BoundaryMode::ALL {
+                                ui.selectable_value(&mut edited.boundary_mode, mode, mode.label());
+                            }
+                        });
+                    ui.end_row();
+
+                    match edited.boundary_mode {
+                        BoundaryMode::Reflect => {
+                            ui.label("Restitution:");
+                            ui.add(egui::DragValue::new(&mut edited.restitution)
+                                .range(0.0..=1.0)
+                                .speed(0.01)
+                                .fixed_decimals(2));
+                            ui.end_row();
+                        }
+                        BoundaryMode::SoftWall => {
+                            ui.label("Raideur des murs:");
+                            ui.add(egui::DragValue::new(&mut edited.wall_stiffness)
+                                .range(1.0..=1000.0)
+                                .speed(1.0));
+                            ui.end_row();
+                        }
+                        BoundaryMode::Unbounded => {
+                            ui.label("Rappel au centre:");
+                            ui.add(egui::DragValue::new(&mut edited.centering_strength)
+                                .range(0.0..=10.0)
+                                .speed(0.01)
+                                .fixed_decimals(2));
+                            ui.end_row();
+                        }
+                        BoundaryMode::Wrap => {}
+                    }
                 });
+
+            ui.add_space(5.0);
+            ui.checkbox(&mut edited.render_interpolation, "Interpoler le rendu entre deux états")
+                .on_hover_text("Lisse l'affichage au prix d'un état de retard");
+
+            ui.add_space(5.0);
+            ui.label(egui::RichText::new(format!(
+                "Friction par pas: {:.4}",
+                edited.friction_per_step(edited.step_dt())
+            ))
+                .small()
+                .color(egui::Color32::GRAY));
+
+            ui.label(egui::RichText::new(format!(
+                "Pas simulés: {} ({} abandonnés)",
+                clock.total_steps, clock.dropped_steps
+            ))
+                .small()
+                .color(egui::Color32::GRAY));
+
+            if clock.dropping {
+                ui.label(egui::RichText::new("⚠ La simulation prend du retard : des pas sont abandonnés. Réduisez la vitesse ou les sous-pas.")
+                    .small()
+                    .color(egui::Color32::from_rgb(255, 165, 0)));
+            }
+
+            if edited.neighbor_search == NeighborSearch::SpatialHash {
+                let grid = GridParams::from(&edited);
+                ui.label(egui::RichText::new(format!(
+                    "Grille: {0}×{0}×{0} cellules de {1:.1} unités (F3 pour vérifier)",
+                    grid.grid_dim, grid.cell_size
+                ))
+                    .small()
+                    .color(egui::Color32::GRAY));
             }
         });
+
+    if edited.physics_differs(&sim_config) || edited.render_interpolation != sim_config.render_interpolation {
+        *sim_config = edited;
+    }
+}
+
+/// Remplace le génome d'une simulation par celui d'un fichier, s'il a le même nombre de types
+fn import_genome(config: &mut ParticleConfig, sim: u32, path: &Path) -> Result<String, String> {
+    let genome = Genome::load(path)?;
+    if genome.num_types != config.num_types {
+        return Err(format!("Le génome a {} types, la simulation en a {}", genome.num_types, config.num_types));
+    }
+
+    let slot = config.genomes.get_mut(sim as usize).ok_or_else(|| format!("Simulation #{} inconnue", sim + 1))?;
+    *slot = genome;
+    Ok(format!("Génome importé depuis {}", path.display()))
 }
 
 // Fonction helper pour obtenir la couleur d'un type